@@ -0,0 +1,280 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::components::{BlindSchedule, GameLimit, GameType, GameVariant};
+use crate::rake::RakeRules;
+
+/// Serialized as `{"type": "BetMade", "params": {...}}`, see `ndjson` for the stream format.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "params")]
+pub enum Action {
+    SeatUpdated(SeatUpdatedParams),
+    StackUpdated(StackUpdatedParams),
+    // NewGameLaunched,
+    GameHandIdSet(String),
+    GameTypeSet(GameType),
+    GameVariantSet(GameVariant),
+    GameLimitSet(GameLimit),
+    RakeRulesSet(RakeRules),
+    GameMaxSeatsSet(u8),
+    GameTableNameSet(String),
+    GameDealerSeatNumSet(u8),
+    CommunityCardsDealt(CommunityCardsDealtParams),
+    NpcCardsDealt(NpcCardsDealtParams),
+    AntePosted(AntePostedParams),
+    BetMade(BetMadeParams),
+    PlayerSatOut(PlayerParams),
+    PlayerSatIn(PlayerParams),
+    PlayerLeft(PlayerParams),
+    PotAwarded(PotAwardedParams),
+    TournamentStarted(TournamentStartedParams),
+    BountySet(BountySetParams),
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeatUpdatedParams {
+    pub name: String,
+    pub seat_num: u8,
+    pub npc: bool,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StackUpdatedParams {
+    pub name: String,
+    pub stack: u64,
+}
+
+/// Names the player of `PlayerSatOut`, `PlayerSatIn` and `PlayerLeft`.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerParams {
+    pub name: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommunityCardsDealtParams {
+    pub prev_cards: Vec<Card>,
+    pub new_cards: Vec<Card>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NpcCardsDealtParams {
+    pub name: String,
+    pub cards: Vec<Card>,
+}
+
+/// Named as suit then rank: `H2`..`HA`, `S2`..`SA`, `D2`..`DA`, `C2`..`CA`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Card {
+    H2,
+    H3,
+    H4,
+    H5,
+    H6,
+    H7,
+    H8,
+    H9,
+    HT,
+    HJ,
+    HQ,
+    HK,
+    HA,
+    S2,
+    S3,
+    S4,
+    S5,
+    S6,
+    S7,
+    S8,
+    S9,
+    ST,
+    SJ,
+    SQ,
+    SK,
+    SA,
+    D2,
+    D3,
+    D4,
+    D5,
+    D6,
+    D7,
+    D8,
+    D9,
+    DT,
+    DJ,
+    DQ,
+    DK,
+    DA,
+    C2,
+    C3,
+    C4,
+    C5,
+    C6,
+    C7,
+    C8,
+    C9,
+    CT,
+    CJ,
+    CQ,
+    CK,
+    CA,
+}
+
+const RANK_CHARS: [char; 13] = [
+    '2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A',
+];
+const SUIT_CHARS: [char; 4] = ['h', 's', 'd', 'c'];
+
+impl Card {
+    pub const ALL: [Card; 52] = [
+        Card::H2,
+        Card::H3,
+        Card::H4,
+        Card::H5,
+        Card::H6,
+        Card::H7,
+        Card::H8,
+        Card::H9,
+        Card::HT,
+        Card::HJ,
+        Card::HQ,
+        Card::HK,
+        Card::HA,
+        Card::S2,
+        Card::S3,
+        Card::S4,
+        Card::S5,
+        Card::S6,
+        Card::S7,
+        Card::S8,
+        Card::S9,
+        Card::ST,
+        Card::SJ,
+        Card::SQ,
+        Card::SK,
+        Card::SA,
+        Card::D2,
+        Card::D3,
+        Card::D4,
+        Card::D5,
+        Card::D6,
+        Card::D7,
+        Card::D8,
+        Card::D9,
+        Card::DT,
+        Card::DJ,
+        Card::DQ,
+        Card::DK,
+        Card::DA,
+        Card::C2,
+        Card::C3,
+        Card::C4,
+        Card::C5,
+        Card::C6,
+        Card::C7,
+        Card::C8,
+        Card::C9,
+        Card::CT,
+        Card::CJ,
+        Card::CQ,
+        Card::CK,
+        Card::CA,
+    ];
+
+    /// 0 for deuce up to 12 for ace.
+    pub fn rank(&self) -> u8 {
+        *self as u8 % 13
+    }
+
+    /// 0..4 in the order hearts, spades, diamonds, clubs.
+    pub fn suit(&self) -> u8 {
+        *self as u8 / 13
+    }
+
+    pub fn from_rank_suit(rank: u8, suit: u8) -> Option<Card> {
+        (rank < 13 && suit < 4).then(|| Card::ALL[usize::from(suit * 13 + rank)])
+    }
+
+    /// Rank letter as written in hand histories: `2`..`9`, `T`, `J`, `Q`, `K`, `A`.
+    pub fn rank_char(&self) -> char {
+        RANK_CHARS[usize::from(self.rank())]
+    }
+
+    pub fn suit_char(&self) -> char {
+        SUIT_CHARS[usize::from(self.suit())]
+    }
+
+    /// Parses hand history notation, e.g. `Ks` or `2h`.
+    pub fn parse(card: &str) -> Option<Card> {
+        let mut chars = card.chars();
+        let rank_char = chars.next()?.to_ascii_uppercase();
+        let suit_char = chars.next()?.to_ascii_lowercase();
+        if chars.next().is_some() {
+            return None;
+        }
+
+        let rank = RANK_CHARS.iter().position(|char| *char == rank_char)?;
+        let suit = SUIT_CHARS.iter().position(|char| *char == suit_char)?;
+        Card::from_rank_suit(rank as u8, suit as u8)
+    }
+}
+
+/// Hand history notation, e.g. `Ks`.
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.rank_char(), self.suit_char())
+    }
+}
+
+/// Cards in hand history notation separated by spaces, e.g. `Ks 2h`.
+pub fn cards_line(cards: &[Card]) -> String {
+    cards
+        .iter()
+        .map(Card::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Ends the hand: the winners collect their amounts from the pot of all bets,
+/// the house keeps what is left as rake.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PotAwardedParams {
+    /// One share per main pot, side pot or split, as the `collected` lines list them.
+    pub pots: Vec<PotShare>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PotShare {
+    pub name: String,
+    /// After rake.
+    pub amount: u64,
+}
+
+/// Sent before the first hand of the tournament.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TournamentStartedParams {
+    pub tournament_id: String,
+    pub schedule: BlindSchedule,
+    /// Prize money from first place down, empty when unknown.
+    #[serde(default)]
+    pub payouts: Vec<u64>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BountySetParams {
+    pub name: String,
+    pub bounty: u64,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BetMadeParams {
+    // TODO: should use name?
+    pub seat_index: u8,
+    pub bet_size: u64,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AntePostedParams {
+    pub seat_index: u8,
+    pub ante: u64,
+}
@@ -0,0 +1,231 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::components::GameType;
+use crate::turn::TurnOrder;
+use crate::view::Street;
+
+/// Sent by `handle_parser_events` once nobody has to act on the street anymore.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BettingRoundClosed {
+    pub street: Street,
+    /// All bets of the hand so far.
+    pub pot: u64,
+}
+
+/// Sent by `handle_parser_events` when a street closes on a bet nobody matched in full.
+/// The unmatched part is already back in `PlayerStack`, as in the hand history line
+/// `Uncalled bet ($2.00) returned to Hero`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UncalledBetReturned {
+    pub name: String,
+    pub seat_num: u8,
+    pub amount: u64,
+}
+
+/// Betting state of one player, as seen after a `BetMade` is applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeatState {
+    pub seat_num: u8,
+    /// Not folded and not all-in.
+    pub can_act: bool,
+    /// Not folded, all-in players included.
+    pub in_hand: bool,
+    /// Keeps the seat without playing, see `PlayerSittingOut`.
+    pub sitting_out: bool,
+    /// Bets of the whole hand, compared with `RoundMaxBet`.
+    pub bets_sum: u64,
+    pub stack: u64,
+}
+
+/// Bet sizes, as in `BetMade`, the player to act may choose from besides checking or folding.
+/// Raises are `None` when the structure or the stack doesn't allow them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BetRange {
+    /// Less than the amount to match when the stack is short.
+    pub call: u64,
+    pub min_raise: Option<u64>,
+    pub max_raise: Option<u64>,
+}
+
+impl BetRange {
+    /// Checks, folds, calls and all-ins for less than a raise are always legal.
+    pub fn allows(&self, bet_size: u64, stack: u64) -> bool {
+        if bet_size <= self.call {
+            return bet_size == 0 || bet_size == self.call;
+        }
+
+        match (self.min_raise, self.max_raise) {
+            (Some(min_raise), Some(max_raise)) => {
+                (min_raise..=max_raise).contains(&bet_size)
+                    || (bet_size == stack && bet_size <= max_raise)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Who acted on the current street. The street is over when every player who
+/// can still bet has matched `RoundMaxBet` and acted since the last full raise.
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct BettingRound {
+    street: Street,
+    blinds_posted: u8,
+    big_blind: u64,
+    /// A smaller all-in raise does not reopen the betting for players who acted.
+    last_raise_size: u64,
+    /// Bet and raises on the street, the big blind counts as the first bet.
+    bets: u8,
+    acted_seats: BTreeSet<u8>,
+    closed: bool,
+}
+
+impl BettingRound {
+    pub fn street(&self) -> Street {
+        self.street
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Blinds posted in the hand, a dead small blind counts as posted.
+    pub fn blinds_posted(&self) -> u8 {
+        self.blinds_posted
+    }
+
+    /// Called before the blinds of the hand are posted.
+    pub fn set_dead_small_blind(&mut self, dead_small_blind: bool) {
+        self.blinds_posted = u8::from(dead_small_blind);
+    }
+
+    pub fn is_blind(&self) -> bool {
+        self.street == Street::Preflop && self.blinds_posted < 2
+    }
+
+    pub fn start_street(&mut self, street: Street) {
+        *self = BettingRound {
+            street,
+            blinds_posted: self.blinds_posted,
+            big_blind: self.big_blind,
+            last_raise_size: self.big_blind,
+            ..Default::default()
+        };
+    }
+
+    /// `raise_size` is how much the bet lifted `RoundMaxBet`.
+    /// The first two bets of the hand are the blinds, posting them is not acting.
+    pub fn record_bet(&mut self, seat_num: u8, bet_size: u64, raise_size: u64) {
+        if self.is_blind() {
+            self.blinds_posted += 1;
+            self.big_blind = self.big_blind.max(bet_size);
+            self.last_raise_size = self.big_blind;
+            self.bets = 1;
+            return;
+        }
+
+        if raise_size > 0 && raise_size >= self.last_raise_size {
+            // Everybody else has to act on the full raise again
+            self.last_raise_size = raise_size;
+            self.acted_seats.clear();
+            self.bets += 1;
+        }
+        self.acted_seats.insert(seat_num);
+    }
+
+    /// Next player clockwise after `seat_num` who has to act, `None` closes the street.
+    pub fn next_to_act(
+        &mut self,
+        seat_num: u8,
+        seat_states: &[SeatState],
+        round_max_bet: u64,
+    ) -> Option<u8> {
+        let in_hand = seat_states
+            .iter()
+            .filter(|seat_state| seat_state.in_hand)
+            .count();
+        let turn_order = TurnOrder::new(
+            seat_states
+                .iter()
+                .map(|seat_state| (seat_state.seat_num, seat_state.can_act)),
+        );
+
+        let can_act = seat_states
+            .iter()
+            .filter(|seat_state| seat_state.can_act)
+            .count();
+
+        // Matched players act again only when somebody else can still bet against them
+        let next_seat_num = turn_order
+            .after(seat_num)
+            .find(|other_seat_num| {
+                seat_states.iter().any(|seat_state| {
+                    seat_state.seat_num == *other_seat_num
+                        && seat_state.can_act
+                        && (seat_state.bets_sum < round_max_bet
+                            || (can_act > 1 && !self.acted_seats.contains(other_seat_num)))
+                })
+            })
+            .filter(|_| in_hand > 1);
+
+        self.closed = next_seat_num.is_none();
+        next_seat_num
+    }
+
+    /// What the player at `seat_num` may bet now. The pot limit counts `pot`,
+    /// all bets and antes of the hand.
+    pub fn bet_range(
+        &self,
+        game_type: GameType,
+        seat_states: &[SeatState],
+        seat_num: u8,
+        round_max_bet: u64,
+        pot: u64,
+    ) -> Option<BetRange> {
+        let seat_state = seat_states
+            .iter()
+            .find(|seat_state| seat_state.seat_num == seat_num)?;
+        let to_call = round_max_bet.saturating_sub(seat_state.bets_sum);
+        let call = to_call.min(seat_state.stack);
+        let reopened = !self.acted_seats.contains(&seat_state.seat_num);
+        if seat_state.stack <= to_call || !reopened {
+            return Some(BetRange {
+                call,
+                ..Default::default()
+            });
+        }
+
+        let min_raise = to_call + self.last_raise_size.max(self.big_blind);
+        let max_raise = match game_type {
+            GameType::NL => seat_state.stack,
+            // Call first, then raise by the whole pot
+            GameType::PL => to_call * 2 + pot,
+            GameType::FL if self.bets >= 4 => {
+                return Some(BetRange {
+                    call,
+                    ..Default::default()
+                })
+            }
+            GameType::FL => to_call + self.fixed_bet_size(),
+        };
+        let min_raise = match game_type {
+            GameType::FL => max_raise,
+            _ => min_raise,
+        };
+
+        Some(BetRange {
+            call,
+            min_raise: Some(min_raise.min(seat_state.stack)),
+            max_raise: Some(max_raise.min(seat_state.stack)),
+        })
+    }
+
+    /// Small bet preflop and on the flop, big bet on the turn and river.
+    fn fixed_bet_size(&self) -> u64 {
+        match self.street {
+            Street::Preflop | Street::Flop => self.big_blind,
+            Street::Turn | Street::River => self.big_blind * 2,
+        }
+    }
+}
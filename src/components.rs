@@ -0,0 +1,435 @@
+use std::fmt;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::action::Card;
+use crate::betting::BetRange;
+use crate::turn::TurnOrder;
+
+// -- Game --
+#[derive(Component, Default, Debug)]
+pub struct Game;
+
+#[derive(Component, Default, Debug)]
+pub struct GameHandId(pub String);
+
+/// Betting structure of the game.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum GameType {
+    /// No limit, any raise up to the whole stack.
+    NL,
+    /// Pot limit, raises up to the size of the pot after calling.
+    PL,
+    /// Fixed limit, small bets preflop and on the flop, big bets on the turn and river,
+    /// at most a bet and three raises per street.
+    FL,
+}
+
+/// Poker variant dealt at the table, Hold'em when the game doesn't say.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameVariant {
+    #[default]
+    Holdem,
+    /// Four hole cards, hands use exactly two of them and three board cards.
+    Omaha,
+    /// Omaha with five hole cards.
+    Omaha5,
+    /// Hold'em with a 36 card deck, deuces to fives removed.
+    /// A flush beats a full house and A-6-7-8-9 is the lowest straight.
+    ShortDeck,
+}
+
+impl GameVariant {
+    pub fn hole_cards(&self) -> usize {
+        match self {
+            GameVariant::Holdem | GameVariant::ShortDeck => 2,
+            GameVariant::Omaha => 4,
+            GameVariant::Omaha5 => 5,
+        }
+    }
+
+    /// Hand history notation, e.g. `Omaha` in `Omaha Pot Limit`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameVariant::Holdem => "Hold'em",
+            GameVariant::Omaha => "Omaha",
+            GameVariant::Omaha5 => "5 Card Omaha",
+            GameVariant::ShortDeck => "6+ Hold'em",
+        }
+    }
+
+    /// `Card::rank` of the lowest card in the deck.
+    pub fn lowest_rank(&self) -> u8 {
+        match self {
+            GameVariant::ShortDeck => 4,
+            GameVariant::Holdem | GameVariant::Omaha | GameVariant::Omaha5 => 0,
+        }
+    }
+
+    pub fn deck(&self) -> Vec<Card> {
+        Card::ALL
+            .iter()
+            .filter(|card| card.rank() >= self.lowest_rank())
+            .copied()
+            .collect()
+    }
+}
+
+/// Stakes of the game. Amounts are integers all over the tracker,
+/// `denomination` of them make one unit of `currency`.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameLimit {
+    pub small_blind: u64,
+    pub big_blind: u64,
+    /// Paid by every player before the hand, 0 without antes.
+    pub ante: u64,
+    pub currency: Currency,
+    /// 100 for money games counted in cents, 1 for chips. A power of 10,
+    /// the tracker rejects limits with other denominations.
+    pub denomination: u64,
+}
+
+impl GameLimit {
+    pub fn new(small_blind: u64, big_blind: u64, currency: Currency) -> Self {
+        GameLimit {
+            small_blind,
+            big_blind,
+            ante: 0,
+            currency,
+            denomination: match currency {
+                Currency::Chips => 1,
+                Currency::USD | Currency::EUR | Currency::GBP => 100,
+            },
+        }
+    }
+
+    pub fn with_ante(self, ante: u64) -> Self {
+        GameLimit { ante, ..self }
+    }
+
+    /// `amount` in big blinds, so stats and strategies compare across stakes.
+    pub fn in_big_blinds(&self, amount: u64) -> f64 {
+        amount as f64 / self.big_blind.max(1) as f64
+    }
+
+    /// Digits after the decimal point of amounts in `currency`, `None` when
+    /// `denomination` is not a power of 10.
+    pub fn decimals(&self) -> Option<usize> {
+        (0..=u64::MAX.ilog10())
+            .find(|decimals| 10u64.pow(*decimals) == self.denomination)
+            .map(|decimals| decimals as usize)
+    }
+
+    /// Hand history notation, e.g. `$0.50` or `1500` for chips.
+    pub fn format_amount(&self, amount: u64) -> String {
+        let symbol = self.currency.symbol();
+        let decimals = match self.decimals() {
+            Some(0) | None => return format!("{symbol}{amount}"),
+            Some(decimals) => decimals,
+        };
+
+        format!(
+            "{symbol}{}.{:0decimals$}",
+            amount / self.denomination,
+            amount % self.denomination
+        )
+    }
+}
+
+/// Stakes as in hand history headers: `$0.50/$1.00 USD` or `50/100` for chips.
+impl fmt::Display for GameLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{}",
+            self.format_amount(self.small_blind),
+            self.format_amount(self.big_blind)
+        )?;
+        match self.currency {
+            Currency::Chips => Ok(()),
+            currency => write!(f, " {currency:?}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Currency {
+    /// Play money and tournaments.
+    #[default]
+    Chips,
+    USD,
+    EUR,
+    GBP,
+}
+
+impl Currency {
+    pub fn from_symbol(symbol: char) -> Option<Self> {
+        match symbol {
+            '$' => Some(Currency::USD),
+            '€' => Some(Currency::EUR),
+            '£' => Some(Currency::GBP),
+            _ => None,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Currency::Chips => "",
+            Currency::USD => "$",
+            Currency::EUR => "€",
+            Currency::GBP => "£",
+        }
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct GameMaxSeats(pub u8);
+
+#[derive(Component, Debug, Clone)]
+pub struct GameTableName(pub String);
+
+#[derive(Component, Default, Debug, Clone, Copy)]
+pub struct DealerSeatNum(pub u8);
+
+/// Button and blinds of a hand. Under the dead button rule the button and
+/// the small blind may be seats nobody plays from anymore.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlindSeats {
+    pub button: u8,
+    pub small_blind: u8,
+    pub big_blind: u8,
+    /// Nobody posts the small blind, its player left or sits out.
+    pub dead_small_blind: bool,
+}
+
+impl BlindSeats {
+    /// Blinds clockwise after a known button, heads-up the button posts the small blind.
+    /// `active_seats` are the seats dealt in.
+    pub fn from_button(button: u8, active_seats: &[u8]) -> Option<Self> {
+        let turn_order = TurnOrder::new(active_seats.iter().map(|seat_num| (*seat_num, true)));
+        let small_blind = if active_seats.len() == 2 && active_seats.contains(&button) {
+            button
+        } else {
+            turn_order.next_to_act(button)?
+        };
+        let big_blind = turn_order.next_to_act(small_blind)?;
+
+        Some(BlindSeats {
+            button,
+            small_blind,
+            big_blind,
+            dead_small_blind: false,
+        })
+    }
+
+    /// Seats of the next hand: the big blind moves on to the next active player,
+    /// the small blind and the button take the seats of the last big and small blind,
+    /// whether somebody still plays there or not. Heads-up the button is the small blind.
+    pub fn next_hand(&self, active_seats: &[u8]) -> Option<Self> {
+        if active_seats.len() < 2 {
+            return None;
+        }
+        let turn_order = TurnOrder::new(active_seats.iter().map(|seat_num| (*seat_num, true)));
+        let big_blind = turn_order.next_to_act(self.big_blind)?;
+
+        if active_seats.len() == 2 {
+            let button = turn_order.next_to_act(big_blind)?;
+            return Some(BlindSeats {
+                button,
+                small_blind: button,
+                big_blind,
+                dead_small_blind: false,
+            });
+        }
+
+        Some(BlindSeats {
+            button: self.small_blind,
+            small_blind: self.big_blind,
+            big_blind,
+            dead_small_blind: !active_seats.contains(&self.big_blind),
+        })
+    }
+}
+// -- Game end --
+
+// -- Tournament --
+#[derive(Component, Default, Debug)]
+pub struct Tournament;
+
+#[derive(Component, Default, Debug, Clone)]
+pub struct TournamentId(pub String);
+
+/// Blinds and ante of one tournament level, in chips.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlindLevel {
+    pub small_blind: u64,
+    pub big_blind: u64,
+    pub ante: u64,
+}
+
+impl BlindLevel {
+    pub fn game_limit(&self) -> GameLimit {
+        GameLimit::new(self.small_blind, self.big_blind, Currency::Chips).with_ante(self.ante)
+    }
+}
+
+/// When the tournament moves on to the next level, checked only when a hand starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LevelProgression {
+    Hands(u32),
+    Minutes(u32),
+}
+
+#[derive(Component, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlindSchedule {
+    pub levels: Vec<BlindLevel>,
+    pub progression: LevelProgression,
+}
+
+impl BlindSchedule {
+    /// The last level lasts until the end of the tournament.
+    pub fn level(&self, index: usize) -> Option<BlindLevel> {
+        self.levels
+            .get(index.min(self.levels.len().saturating_sub(1)))
+            .copied()
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TournamentLevel {
+    /// Index in `BlindSchedule::levels`.
+    pub index: usize,
+    /// Hands started on this level.
+    pub hands: u32,
+    /// `Time::elapsed` when the level started.
+    pub started: Duration,
+}
+
+impl TournamentLevel {
+    /// Moves on to the next level if the current one is over, then counts the hand.
+    pub fn start_hand(&mut self, schedule: &BlindSchedule, now: Duration) {
+        let has_next_level = |level: &TournamentLevel| level.index + 1 < schedule.levels.len();
+
+        match schedule.progression {
+            LevelProgression::Hands(hands) => {
+                if self.hands >= hands && has_next_level(self) {
+                    self.index += 1;
+                    self.hands = 0;
+                    self.started = now;
+                }
+            }
+            LevelProgression::Minutes(minutes) => {
+                let level_time = Duration::from_secs(u64::from(minutes) * 60);
+                // Levels keep running between hands, a long break may skip some
+                while now.saturating_sub(self.started) >= level_time && has_next_level(self) {
+                    self.index += 1;
+                    self.hands = 0;
+                    self.started += level_time;
+                }
+            }
+        }
+        self.hands += 1;
+    }
+}
+
+/// Prize money from first place down.
+#[derive(Component, Default, Debug, Clone)]
+pub struct TournamentPayouts(pub Vec<u64>);
+
+/// Hand ids played in the tournament, in order.
+#[derive(Component, Default, Debug, Clone)]
+pub struct TournamentHands(pub Vec<String>);
+
+#[derive(Component, Default, Debug, Clone)]
+pub struct TournamentEliminations(pub Vec<Elimination>);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Elimination {
+    pub name: String,
+    /// Finishing place, 2 for the runner-up.
+    pub place: u32,
+    pub hand_id: Option<String>,
+    /// Bounty on the player's head when they busted.
+    pub bounty: Option<u64>,
+}
+// -- Tournament end --
+
+// -- Round --
+#[derive(Component, Default, Debug)]
+pub struct RoundMaxBet(pub u64);
+// -- Round end --
+
+// -- Board --
+#[derive(Component, Default, Debug)]
+pub struct Board;
+
+#[derive(Component, Default, Debug)]
+pub struct BoardCards(pub Vec<Card>);
+// -- Board end --
+
+// -- Player --
+#[derive(Component, Default, Debug)]
+pub struct Player;
+
+#[derive(Component, Default, Debug,  Clone)]
+pub struct PlayerName(pub String);
+
+#[derive(Component, Default, Debug)]
+pub struct PlayerSeatNum(pub u8);
+
+#[derive(Component, Debug)]
+pub struct PlayerNpc;
+
+#[derive(Component, Default, Debug)]
+pub struct PlayerStack(pub u64);
+
+#[derive(Component, Default, Debug)]
+pub struct PlayerCards(pub Vec<Card>);
+
+#[derive(Component, Default, Debug)]
+pub struct PlayerRoundBetting(pub bool);
+
+#[derive(Component, Default, Debug, Clone)]
+pub struct PlayerRoundBets(pub Vec<u64>);
+
+impl PlayerRoundBets {
+    pub fn bets_sum(&self) -> u64 {
+        self.0.iter().sum()
+    }
+}
+
+/// Ante of the current hand. In the pot, but not part of the bets to match.
+#[derive(Component, Default, Debug, Clone, Copy)]
+pub struct PlayerAnte(pub u64);
+
+#[derive(Component, Default, Debug)]
+pub struct Dealer;
+
+#[derive(Component, Default, Debug)]
+pub struct NeedDecision(pub bool);
+
+/// Tournament bounty on the player, paid to whoever eliminates them.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct PlayerBounty(pub u64);
+
+/// Busted out of the tournament, stacks can't be topped up anymore.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct PlayerEliminated {
+    pub place: u32,
+}
+
+/// Keeps the seat but takes no part in hands: skipped by turn order and blinds.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct PlayerSittingOut;
+
+/// Left the table with chips in the pot, despawned when the next hand starts.
+/// Not in `TrackerState::players_hmap` anymore and sitting out until then.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct PlayerLeaving;
+
+/// What the player asked for a decision may bet, see `BettingRound::bet_range`.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct PlayerBetRange(pub BetRange);
+// -- Player end --
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+use std::mem;
+
+use bevy::ecs::event::{Events, ManualEventReader};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::action::{Action, PlayerParams};
+use crate::components::{PlayerAnte, PlayerName, PlayerRoundBets, PlayerStack};
+use crate::error::{TrackerError, TrackerErrors};
+use crate::rake::HandRake;
+
+/// Chips the current hand started with. Bets and antes only move chips from stacks
+/// to `PlayerRoundBets` and `PlayerAnte` (the pot), so their sum must not change until the next hand,
+/// except for the rake taken when the pot is awarded and the stacks of players who left.
+#[derive(Resource, Default, Debug, Clone, Serialize, Deserialize)]
+pub struct ChipConservation {
+    hand_chips: Option<u64>,
+    broken: bool,
+    /// Stack, bets and ante of every player after the last checked batch,
+    /// what a `PlayerLeft` takes off the table.
+    #[serde(default)]
+    player_chips: HashMap<String, u64>,
+}
+
+/// Debug check, runs after the commands of `handle_parser_events` are applied.
+/// Stacks sent before the first bet of a hand set the starting amount,
+/// later the first batch that changes it is reported by its last action, once per hand.
+#[allow(clippy::type_complexity)]
+pub fn check_chip_conservation_system(
+    event_source: Res<Events<Action>>,
+    mut event_reader: Local<Option<ManualEventReader<Action>>>,
+    mut chip_conservation: ResMut<ChipConservation>,
+    players_query: Query<(
+        &PlayerName,
+        Option<&PlayerStack>,
+        Option<&PlayerRoundBets>,
+        Option<&PlayerAnte>,
+    )>,
+    hand_rake_query: Query<&HandRake>,
+    mut tracker_errors: ResMut<TrackerErrors>,
+) {
+    let actions = event_reader
+        .get_or_insert_with(|| event_source.get_reader())
+        .iter(&event_source)
+        .cloned()
+        .collect::<Vec<_>>();
+    if actions.is_empty() {
+        return;
+    }
+
+    let prev_player_chips = mem::replace(
+        &mut chip_conservation.player_chips,
+        players_query
+            .iter()
+            .map(
+                |(player_name, player_stack, player_round_bets, player_ante)| {
+                    let player_chips = player_stack.map_or(0, |player_stack| player_stack.0)
+                        + player_round_bets.map_or(0, PlayerRoundBets::bets_sum)
+                        + player_ante.map_or(0, |player_ante| player_ante.0);
+                    (player_name.0.clone(), player_chips)
+                },
+            )
+            .collect(),
+    );
+    let (stacks, bets) = players_query.iter().fold(
+        (0, 0),
+        |(stacks, bets), (_, player_stack, player_round_bets, player_ante)| {
+            (
+                stacks + player_stack.map_or(0, |player_stack| player_stack.0),
+                bets + player_round_bets.map_or(0, PlayerRoundBets::bets_sum)
+                    + player_ante.map_or(0, |player_ante| player_ante.0),
+            )
+        },
+    );
+    let chips = stacks + bets;
+
+    if actions
+        .iter()
+        .any(|action| matches!(action, Action::GameHandIdSet(_)))
+    {
+        *chip_conservation = ChipConservation {
+            player_chips: mem::take(&mut chip_conservation.player_chips),
+            ..default()
+        };
+    }
+
+    let seating = actions
+        .iter()
+        .any(|action| matches!(action, Action::SeatUpdated(_) | Action::StackUpdated(_)));
+    if seating && bets == 0 {
+        chip_conservation.hand_chips = Some(chips);
+        return;
+    }
+
+    let player_chips = &chip_conservation.player_chips;
+    let left_chips = actions
+        .iter()
+        .filter_map(|action| match action {
+            // Still seated when the player has chips in the pot, until the hand is over
+            Action::PlayerLeft(PlayerParams { name }) if !player_chips.contains_key(name) => {
+                prev_player_chips.get(name).copied()
+            }
+            _ => None,
+        })
+        .sum::<u64>();
+    let raked = if actions
+        .iter()
+        .any(|action| matches!(action, Action::PotAwarded(_)))
+    {
+        hand_rake_query.iter().next().map_or(0, HandRake::total)
+    } else {
+        0
+    };
+    let Some(hand_chips) = chip_conservation.hand_chips.as_mut() else {
+        return;
+    };
+    *hand_chips = hand_chips.saturating_sub(left_chips + raked);
+
+    let hand_chips = *hand_chips;
+    if chips == hand_chips || chip_conservation.broken {
+        return;
+    }
+
+    chip_conservation.broken = true;
+    let index = actions.len() - 1;
+    tracker_errors.0.push(TrackerError::ChipsNotConserved {
+        index,
+        action: actions[index].clone(),
+        expected: hand_chips,
+        actual: chips,
+    });
+}
@@ -0,0 +1,174 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::action::Card;
+use crate::betting::BetRange;
+use crate::components::{GameLimit, GameVariant};
+use crate::equity::equity_vs_random;
+use crate::icm::{all_in_delta, EvDelta};
+use crate::plugin::DisplayMode;
+use crate::view::{PlayerStatus, TableView};
+
+/// Deals behind the win probability of `DecisionRequest::ev_delta`.
+const ALL_IN_EQUITY_SAMPLES: usize = 2_000;
+
+/// Decision of the current table, rebuilt once per `TableView` change
+/// so the all-in equity is sampled once per decision point.
+#[derive(Resource, Debug, Clone, Default, PartialEq)]
+pub struct PendingDecision(pub Option<DecisionRequest>);
+
+pub fn update_pending_decision_system(
+    table_view: Res<TableView>,
+    mut pending_decision: ResMut<PendingDecision>,
+) {
+    if table_view.is_changed() {
+        pending_decision.0 = DecisionRequest::from_view(&table_view);
+    }
+}
+
+/// Decisions are printed only to the console display, the terminal UI marks
+/// the player to act itself.
+pub fn make_decision_system(
+    pending_decision: Res<PendingDecision>,
+    decision_mode: Res<DecisionMode>,
+    display_mode: Res<DisplayMode>,
+) {
+    trace!("Try make_decision_system");
+    if !pending_decision.is_changed() || *display_mode != DisplayMode::Console {
+        return;
+    }
+
+    if let Some(decision_request) = &pending_decision.0 {
+        println!("Will generate decision for {decision_request:?}");
+        if let Some(push_fold) = decision_request.push_fold(*decision_mode) {
+            println!("Push/fold by {:?}: {push_fold:?}", *decision_mode);
+        }
+    };
+}
+
+/// What the push/fold logic maximizes.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DecisionMode {
+    #[default]
+    ChipEv,
+    /// Tournament prize money, tighter than chip EV near the money.
+    Icm,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PushFold {
+    Push,
+    Fold,
+}
+
+/// What the tracked NPC has to decide on, taken from a `TableView`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecisionRequest {
+    pub name: String,
+    pub seat_num: u8,
+    pub stack: u64,
+    pub to_call: u64,
+    pub cards: Vec<Card>,
+    pub board: Vec<Card>,
+    /// Raises allowed by the betting structure, `None` before the tracker knows it.
+    pub bet_range: Option<BetRange>,
+    /// Decides how `cards` play, see `evaluator::best_hand`.
+    pub game_variant: GameVariant,
+    /// Stakes to size the decision in big blinds.
+    pub game_limit: Option<GameLimit>,
+    /// All-in against the biggest stack left in the hand compared with folding,
+    /// only in tournaments with known payouts.
+    pub ev_delta: Option<EvDelta>,
+}
+
+impl DecisionRequest {
+    pub fn from_view(table_view: &TableView) -> Option<Self> {
+        table_view
+            .players
+            .iter()
+            .find(|player| player.need_decision)
+            .map(|player| DecisionRequest {
+                ev_delta: all_in_ev_delta(table_view, player.seat_num),
+                name: player.name.clone(),
+                seat_num: player.seat_num,
+                stack: player.stack.unwrap_or_default(),
+                to_call: table_view
+                    .round_max_bet
+                    .saturating_sub(player.round_bets_sum()),
+                cards: player.cards.clone(),
+                board: table_view.board.clone(),
+                bet_range: player.bet_range,
+                game_variant: table_view.game_variant.unwrap_or_default(),
+                game_limit: table_view.game_limit,
+            })
+    }
+
+    /// `None` when there is no `ev_delta` to decide on.
+    pub fn push_fold(&self, decision_mode: DecisionMode) -> Option<PushFold> {
+        let ev_delta = self.ev_delta?;
+        let delta = match decision_mode {
+            DecisionMode::ChipEv => ev_delta.chip_ev,
+            DecisionMode::Icm => ev_delta.dollar_ev,
+        };
+
+        Some(if delta > 0.0 {
+            PushFold::Push
+        } else {
+            PushFold::Fold
+        })
+    }
+}
+
+fn all_in_ev_delta(table_view: &TableView, seat_num: u8) -> Option<EvDelta> {
+    let payouts = table_view
+        .tournament
+        .as_ref()
+        .map(|tournament| &tournament.payouts)
+        .filter(|payouts| !payouts.is_empty())?;
+    let players = table_view
+        .players
+        .iter()
+        .filter(|player| player.status != PlayerStatus::Eliminated && player.stack.is_some())
+        .collect::<Vec<_>>();
+    let hero = players
+        .iter()
+        .position(|player| player.seat_num == seat_num)?;
+    let caller = players
+        .iter()
+        .enumerate()
+        .filter(|(index, player)| {
+            *index != hero && matches!(player.status, PlayerStatus::Active | PlayerStatus::AllIn)
+        })
+        .max_by_key(|(_, player)| player.stack.unwrap_or_default() + player.committed())
+        .map(|(index, _)| index)?;
+    if players[hero].cards.is_empty() {
+        return None;
+    }
+
+    let game_variant = table_view.game_variant.unwrap_or_default();
+    let win_probability = equity_vs_random(
+        game_variant,
+        &players[hero].cards,
+        &table_view.board,
+        1,
+        ALL_IN_EQUITY_SAMPLES,
+        1,
+    );
+    let stacks = players
+        .iter()
+        .map(|player| player.stack.unwrap_or_default())
+        .collect::<Vec<_>>();
+    let bets = players
+        .iter()
+        .map(|player| player.committed())
+        .collect::<Vec<_>>();
+
+    Some(all_in_delta(
+        &stacks,
+        &bets,
+        payouts,
+        hero,
+        caller,
+        win_probability,
+    ))
+}
@@ -0,0 +1,82 @@
+use std::fmt::Debug;
+
+use bevy::ecs::archetype::Archetypes;
+use bevy::ecs::component::{ComponentId, Components};
+use bevy::prelude::*;
+
+use crate::view::TableView;
+
+pub fn inspect_changes_system<T: Component + Debug>(q: Query<Ref<T>>) {
+    // Iterate over each component of type `T` and log its changed status.
+    for val in &q {
+        if val.is_changed() {
+            println!(
+                "Value `{val:?}` was last changed at tick {}.",
+                val.last_changed()
+            );
+        } else {
+            println!("Value `{val:?}` is unchanged.");
+        }
+    }
+}
+
+// -- For console history --
+pub fn show_all_players_system(table_view: Res<TableView>) {
+    println!("----- Players ----------");
+    table_view
+        .players
+        .iter()
+        .for_each(|player_view| println!("{player_view:?}"));
+    println!("========================");
+}
+
+pub fn show_game_system(table_view: Res<TableView>) {
+    println!("----- Game ----------");
+    println!(
+        "{:?} {:?} {:?} max seats {:?}, dealer seat {:?}",
+        table_view.hand_id,
+        table_view.game_type,
+        table_view.game_limit,
+        table_view.max_seats,
+        table_view.dealer_seat_num
+    );
+    println!("========================");
+}
+
+pub fn show_board_system(table_view: Res<TableView>) {
+    println!("----- Board ----------");
+    println!(
+        "{:?} {:?}, pot {}",
+        table_view.street, table_view.board, table_view.pot
+    );
+    println!("========================");
+}
+
+pub fn show_entity_state_system(
+    archetypes: &Archetypes,
+    components: &Components,
+    entities: Query<Entity>,
+) {
+    for entity in &entities {
+        println!("----- start ----------");
+        println!("Entity: {:?}", entity);
+        for comp_id in get_components_for_entity(&entity, archetypes).unwrap() {
+            if let Some(comp_info) = components.get_info(comp_id) {
+                println!("Component: {:?}", comp_info);
+            }
+        }
+        println!("----- end ----------");
+    }
+}
+
+pub fn get_components_for_entity<'a>(
+    entity: &Entity,
+    archetypes: &'a Archetypes,
+) -> Option<impl Iterator<Item = ComponentId> + 'a> {
+    for archetype in archetypes.iter() {
+        if archetype.entities().iter().any(|e| e.entity() == *entity) {
+            return Some(archetype.components());
+        }
+    }
+    None
+}
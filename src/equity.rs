@@ -0,0 +1,148 @@
+use std::collections::HashSet;
+
+use crate::action::Card;
+use crate::components::GameVariant;
+use crate::evaluator::best_hand;
+
+/// Runouts sampled by `equity` when there are too many to enumerate.
+pub const DEFAULT_SAMPLES: usize = 10_000;
+
+/// Share of the pot each of `hands` wins at showdown, split pots shared.
+/// Runouts are enumerated when at most two board cards are missing,
+/// otherwise `samples` of them are dealt with a generator seeded by `seed`.
+pub fn equity(
+    game_variant: GameVariant,
+    hands: &[Vec<Card>],
+    board: &[Card],
+    samples: usize,
+    seed: u64,
+) -> Vec<f64> {
+    let known_cards = hands
+        .iter()
+        .flatten()
+        .chain(board)
+        .copied()
+        .collect::<HashSet<_>>();
+    let deck = game_variant
+        .deck()
+        .into_iter()
+        .filter(|card| !known_cards.contains(card))
+        .collect::<Vec<_>>();
+    let missing = 5usize.saturating_sub(board.len());
+
+    let mut shares = vec![0.0; hands.len()];
+    let mut runouts = 0;
+    let mut add_runout = |runout: &[Card]| {
+        let full_board = board.iter().chain(runout).copied().collect::<Vec<_>>();
+        let hand_ranks = hands
+            .iter()
+            .map(|hand| best_hand(game_variant, hand, &full_board))
+            .collect::<Vec<_>>();
+        let best_rank = hand_ranks.iter().max().copied().flatten();
+        let winners = hand_ranks
+            .iter()
+            .filter(|hand_rank| **hand_rank == best_rank)
+            .count();
+        hand_ranks
+            .iter()
+            .zip(shares.iter_mut())
+            .filter(|(hand_rank, _)| **hand_rank == best_rank)
+            .for_each(|(_, share)| *share += 1.0 / winners as f64);
+        runouts += 1;
+    };
+
+    match missing {
+        0 => add_runout(&[]),
+        1 => deck.iter().for_each(|card| add_runout(&[*card])),
+        2 => {
+            for (index, first) in deck.iter().enumerate() {
+                deck[index + 1..]
+                    .iter()
+                    .for_each(|second| add_runout(&[*first, *second]));
+            }
+        }
+        _ => {
+            let mut rng = XorShift(seed.max(1));
+            let mut deck = deck;
+            for _ in 0..samples {
+                // Partial Fisher-Yates, the runout ends up in front
+                for index in 0..missing.min(deck.len()) {
+                    let swap_index = index + rng.below(deck.len() - index);
+                    deck.swap(index, swap_index);
+                }
+                add_runout(&deck[..missing.min(deck.len())]);
+            }
+        }
+    }
+
+    shares
+        .into_iter()
+        .map(|share| share / runouts.max(1) as f64)
+        .collect()
+}
+
+/// Share of the pot `hand` wins against `opponents` random hands of the variant,
+/// over `samples` deals from a generator seeded by `seed`.
+pub fn equity_vs_random(
+    game_variant: GameVariant,
+    hand: &[Card],
+    board: &[Card],
+    opponents: usize,
+    samples: usize,
+    seed: u64,
+) -> f64 {
+    let mut deck = game_variant
+        .deck()
+        .into_iter()
+        .filter(|card| !hand.contains(card) && !board.contains(card))
+        .collect::<Vec<_>>();
+    let hole_cards = game_variant.hole_cards();
+    let missing = 5usize.saturating_sub(board.len());
+    let dealt = opponents * hole_cards + missing;
+    if dealt > deck.len() || samples == 0 {
+        return 0.0;
+    }
+
+    let mut rng = XorShift(seed.max(1));
+    let mut share = 0.0;
+    for _ in 0..samples {
+        for index in 0..dealt {
+            let swap_index = index + rng.below(deck.len() - index);
+            deck.swap(index, swap_index);
+        }
+        let full_board = board
+            .iter()
+            .chain(&deck[..missing])
+            .copied()
+            .collect::<Vec<_>>();
+        let hand_rank = best_hand(game_variant, hand, &full_board);
+        let opponent_ranks = deck[missing..dealt]
+            .chunks(hole_cards)
+            .map(|opponent_hand| best_hand(game_variant, opponent_hand, &full_board))
+            .collect::<Vec<_>>();
+
+        if opponent_ranks
+            .iter()
+            .all(|opponent_rank| *opponent_rank <= hand_rank)
+        {
+            let winners = 1 + opponent_ranks
+                .iter()
+                .filter(|opponent_rank| **opponent_rank == hand_rank)
+                .count();
+            share += 1.0 / winners as f64;
+        }
+    }
+
+    share / samples as f64
+}
+
+struct XorShift(u64);
+
+impl XorShift {
+    fn below(&mut self, bound: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % bound as u64) as usize
+    }
+}
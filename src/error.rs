@@ -0,0 +1,194 @@
+use std::fmt;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::action::{Action, Card};
+use crate::betting::BetRange;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TrackerError {
+    UnknownPlayer(String),
+    UnknownSeat(u8),
+    /// Stack update for a player already out of the tournament.
+    PlayerEliminated(String),
+    /// Bet from a player who sits out or left the table.
+    PlayerSittingOut(String),
+    /// Bet before any `GameHandIdSet`.
+    NoHand,
+    /// Bet from a seat whose stack was never set.
+    UnknownStack(u8),
+    BetExceedsStack {
+        seat_num: u8,
+        bet_size: u64,
+        stack: u64,
+    },
+    DuplicateCards(Vec<Card>),
+    /// Cards the deck of the game variant doesn't have, e.g. deuces in short deck.
+    CardsNotInDeck(Vec<Card>),
+    /// Hole cards don't match the game variant, e.g. two cards dealt in Omaha.
+    WrongHoleCards {
+        name: String,
+        expected: usize,
+        actual: usize,
+    },
+    /// The hand puts the button elsewhere than the rotation predicted.
+    UnexpectedButton {
+        expected: u8,
+        actual: u8,
+    },
+    /// A blind posted from another seat than predicted for the hand.
+    UnexpectedBlind {
+        seat_num: u8,
+        expected: u8,
+    },
+    /// Amounts of the limit can't be written as decimals of its currency.
+    UnsupportedDenomination(u64),
+    /// The bet doesn't fit the betting structure of the game.
+    IllegalBet {
+        seat_num: u8,
+        bet_size: u64,
+        bet_range: BetRange,
+    },
+    /// Winners collected more than the pot holds.
+    PotOverAwarded {
+        pot: u64,
+        awarded: u64,
+    },
+    /// What the winners left in the pot is not the rake of the table rules.
+    RakeMismatch {
+        expected: u64,
+        actual: u64,
+    },
+    /// A watched hand history file can't be read.
+    HandHistoryUnreadable(String),
+    /// A hand of a watched file doesn't parse, its actions were not sent.
+    HandSkipped(String),
+    /// Handling a batch panicked, the session can't track the table anymore.
+    SessionFailed(String),
+    /// Stacks and bets stopped adding up to the chips the hand started with.
+    /// `action` at `index` of the batch is the first one that broke it, when a `TableSession`
+    /// replayed the batch. The check alone only knows the batch and names its last action.
+    ChipsNotConserved {
+        index: usize,
+        action: Action,
+        expected: u64,
+        actual: u64,
+    },
+}
+
+impl TrackerError {
+    /// Nothing of the batch was rejected: the action was applied anyway and the tracker
+    /// only disagrees with the table about it, or the report is about the input source.
+    pub fn is_warning(&self) -> bool {
+        matches!(
+            self,
+            TrackerError::UnexpectedButton { .. }
+                | TrackerError::UnexpectedBlind { .. }
+                | TrackerError::RakeMismatch { .. }
+                | TrackerError::ChipsNotConserved { .. }
+                | TrackerError::HandHistoryUnreadable(_)
+                | TrackerError::HandSkipped(_)
+        )
+    }
+}
+
+impl fmt::Display for TrackerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrackerError::UnknownPlayer(name) => write!(f, "unknown player {name:?}"),
+            TrackerError::UnknownSeat(seat_num) => write!(f, "no player at seat {seat_num}"),
+            TrackerError::PlayerEliminated(name) => {
+                write!(f, "player {name:?} is eliminated from the tournament")
+            }
+            TrackerError::PlayerSittingOut(name) => {
+                write!(f, "player {name:?} is sitting out and can't bet")
+            }
+            TrackerError::NoHand => write!(f, "no hand was started"),
+            TrackerError::UnknownStack(seat_num) => {
+                write!(f, "stack of the player at seat {seat_num} is unknown")
+            }
+            TrackerError::BetExceedsStack {
+                seat_num,
+                bet_size,
+                stack,
+            } => write!(
+                f,
+                "bet {bet_size} at seat {seat_num} is bigger than the stack {stack}"
+            ),
+            TrackerError::DuplicateCards(cards) => write!(f, "cards {cards:?} are already known"),
+            TrackerError::CardsNotInDeck(cards) => {
+                write!(f, "cards {cards:?} are not in the deck of the game")
+            }
+            TrackerError::WrongHoleCards {
+                name,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{name:?} was dealt {actual} hole cards, the game deals {expected}"
+            ),
+            TrackerError::UnexpectedButton { expected, actual } => write!(
+                f,
+                "button is at seat {actual}, expected it at seat {expected}"
+            ),
+            TrackerError::UnexpectedBlind { seat_num, expected } => write!(
+                f,
+                "blind posted at seat {seat_num}, expected it from seat {expected}"
+            ),
+            TrackerError::UnsupportedDenomination(denomination) => {
+                write!(f, "denomination {denomination} is not a power of 10")
+            }
+            TrackerError::IllegalBet {
+                seat_num,
+                bet_size,
+                bet_range,
+            } => write!(
+                f,
+                "bet {bet_size} at seat {seat_num} is not allowed, expected {bet_range:?}"
+            ),
+            TrackerError::PotOverAwarded { pot, awarded } => {
+                write!(f, "winners collected {awarded} from a pot of {pot}")
+            }
+            TrackerError::RakeMismatch { expected, actual } => write!(
+                f,
+                "house took {actual} from the pot, the rake rules take {expected}"
+            ),
+            TrackerError::HandHistoryUnreadable(message) => {
+                write!(f, "can't read hand history {message}")
+            }
+            TrackerError::HandSkipped(message) => write!(f, "skipped hand from {message}"),
+            TrackerError::SessionFailed(message) => {
+                write!(f, "table session failed and stopped tracking - {message}")
+            }
+            TrackerError::ChipsNotConserved {
+                index,
+                action,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "action {index} {action:?} breaks chip conservation: stacks and bets hold {actual}, expected {expected}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TrackerError {}
+
+/// Errors reported by validators and handlers, drained by the caller after `app.update()`.
+#[derive(Resource, Default, Debug, Clone, PartialEq)]
+pub struct TrackerErrors(pub Vec<TrackerError>);
+
+impl fmt::Display for TrackerErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().enumerate().try_for_each(|(index, err)| {
+            if index > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{err}")
+        })
+    }
+}
+
+impl std::error::Error for TrackerErrors {}
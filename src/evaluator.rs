@@ -0,0 +1,148 @@
+use serde::{Deserialize, Serialize};
+
+use crate::action::Card;
+use crate::components::GameVariant;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum HandCategory {
+    HighCard,
+    Pair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+}
+
+/// Strength of a five card hand, a greater `HandRank` wins.
+/// Compare only ranks of the same `GameVariant`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct HandRank {
+    /// Position of `category` in the hand ranking of the variant.
+    strength: u8,
+    pub category: HandCategory,
+    /// `Card::rank` values breaking ties within the category, most significant first.
+    pub ranks: [u8; 5],
+}
+
+/// Ranks exactly five cards by the hand ranking of `game_variant`.
+pub fn evaluate_five(game_variant: GameVariant, cards: [Card; 5]) -> HandRank {
+    let mut rank_counts = [0u8; 13];
+    cards
+        .iter()
+        .for_each(|card| rank_counts[usize::from(card.rank())] += 1);
+
+    // Pairs before kickers, higher ranks first within the same count
+    let mut groups = (0..13u8)
+        .rev()
+        .filter(|rank| rank_counts[usize::from(*rank)] > 0)
+        .map(|rank| (rank_counts[usize::from(rank)], rank))
+        .collect::<Vec<_>>();
+    groups.sort_by_key(|(count, rank)| std::cmp::Reverse((*count, *rank)));
+
+    let mut ranks = [0u8; 5];
+    groups
+        .iter()
+        .flat_map(|(count, rank)| (0..*count).map(move |_| *rank))
+        .zip(ranks.iter_mut())
+        .for_each(|(rank, slot)| *slot = rank);
+
+    let is_flush = cards.iter().all(|card| card.suit() == cards[0].suit());
+    // The ace plays low below the lowest card of the deck: A-2-3-4-5, or A-6-7-8-9 in short deck
+    let lowest_rank = game_variant.lowest_rank();
+    let straight_high = (groups.len() == 5)
+        .then(|| match (groups[0].1, groups[4].1) {
+            (high, low) if high - low == 4 => Some(high),
+            (12, low) if low == lowest_rank && groups[1].1 == lowest_rank + 3 => {
+                Some(lowest_rank + 3)
+            }
+            _ => None,
+        })
+        .flatten();
+
+    let category = match (straight_high, is_flush, groups[0].0, groups[1].0) {
+        (Some(_), true, ..) => HandCategory::StraightFlush,
+        (_, _, 4, _) => HandCategory::FourOfAKind,
+        (_, _, 3, 2) => HandCategory::FullHouse,
+        (_, true, ..) => HandCategory::Flush,
+        (Some(_), ..) => HandCategory::Straight,
+        (_, _, 3, _) => HandCategory::ThreeOfAKind,
+        (_, _, 2, 2) => HandCategory::TwoPair,
+        (_, _, 2, _) => HandCategory::Pair,
+        _ => HandCategory::HighCard,
+    };
+    if let Some(straight_high) = straight_high {
+        ranks = [straight_high, 0, 0, 0, 0];
+    }
+
+    let strength = match (game_variant, category) {
+        (GameVariant::ShortDeck, HandCategory::Flush) => HandCategory::FullHouse as u8,
+        (GameVariant::ShortDeck, HandCategory::FullHouse) => HandCategory::Flush as u8,
+        _ => category as u8,
+    };
+
+    HandRank {
+        strength,
+        category,
+        ranks,
+    }
+}
+
+/// Best hand the player can make on the board. Hold'em and short deck use any five cards,
+/// Omaha exactly two hole cards and three board cards.
+/// `None` until there are enough cards to make a hand.
+pub fn best_hand(
+    game_variant: GameVariant,
+    hole_cards: &[Card],
+    board: &[Card],
+) -> Option<HandRank> {
+    match game_variant {
+        GameVariant::Holdem | GameVariant::ShortDeck => {
+            let cards = hole_cards.iter().chain(board).copied().collect::<Vec<_>>();
+            combinations(&cards, 5)
+                .into_iter()
+                .map(|hand| evaluate_five(game_variant, to_five(&hand, &[])))
+                .max()
+        }
+        GameVariant::Omaha | GameVariant::Omaha5 => {
+            let board_combinations = combinations(board, 3);
+            combinations(hole_cards, 2)
+                .iter()
+                .flat_map(|hole_pair| {
+                    board_combinations.iter().map(move |board_three| {
+                        evaluate_five(game_variant, to_five(hole_pair, board_three))
+                    })
+                })
+                .max()
+        }
+    }
+}
+
+fn to_five(first: &[Card], second: &[Card]) -> [Card; 5] {
+    let mut hand = [Card::H2; 5];
+    first
+        .iter()
+        .chain(second)
+        .zip(hand.iter_mut())
+        .for_each(|(card, slot)| *slot = *card);
+    hand
+}
+
+/// Every `k` cards out of `cards`, in the original order.
+fn combinations(cards: &[Card], k: usize) -> Vec<Vec<Card>> {
+    if k == 0 {
+        return vec![vec![]];
+    }
+    if cards.len() < k {
+        return vec![];
+    }
+
+    let mut with_first = combinations(&cards[1..], k - 1);
+    with_first
+        .iter_mut()
+        .for_each(|combination| combination.insert(0, cards[0]));
+    with_first.extend(combinations(&cards[1..], k));
+    with_first
+}
@@ -0,0 +1,672 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write;
+
+use bevy::ecs::event::{Events, ManualEventReader};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::action::*;
+use crate::components::{BlindSeats, GameLimit, GameType, GameVariant};
+use crate::rake::{HandRake, RakeRules};
+use crate::tracker::TrackerState;
+
+/// Hands kept in the journal: the current one and the one before it.
+const KEPT_HANDS: usize = 2;
+
+/// Actions handled by the tracker, in the order they were processed.
+/// Older hands are folded into the seats, stacks and game settings they left.
+#[derive(Resource, Default, Debug, Clone, Serialize, Deserialize)]
+pub struct ActionJournal {
+    actions: Vec<Action>,
+    /// Button and blinds the tracker settled on, for the kept hands.
+    blind_seats: Vec<(String, BlindSeats)>,
+}
+
+impl ActionJournal {
+    /// Setup actions of the trimmed hands, then the kept hands.
+    pub fn actions(&self) -> &[Action] {
+        &self.actions
+    }
+
+    pub fn push(&mut self, action: Action) {
+        if matches!(action, Action::GameHandIdSet(_)) {
+            self.trim();
+        }
+        self.actions.push(action);
+    }
+
+    /// Actions starting from the last `GameHandIdSet`.
+    pub fn last_hand(&self) -> &[Action] {
+        let hand_start = self
+            .actions
+            .iter()
+            .rposition(|action| matches!(action, Action::GameHandIdSet(_)))
+            .unwrap_or(self.actions.len());
+
+        &self.actions[hand_start..]
+    }
+
+    /// Journal from the very start up to the end of the hand `hand_id`,
+    /// ready for `export_pokerstars`.
+    pub fn until_hand_end(&self, hand_id: &str) -> Option<&[Action]> {
+        let hand_start = self.actions.iter().position(
+            |action| matches!(action, Action::GameHandIdSet(id) if id.as_str() == hand_id),
+        )?;
+        let hand_end = self.actions[hand_start + 1..]
+            .iter()
+            .position(|action| matches!(action, Action::GameHandIdSet(_)))
+            .map_or(self.actions.len(), |index| hand_start + 1 + index);
+
+        Some(&self.actions[..hand_end])
+    }
+
+    pub fn blind_seats(&self, hand_id: &str) -> Option<BlindSeats> {
+        self.blind_seats
+            .iter()
+            .find(|(blind_seats_hand_id, _)| blind_seats_hand_id == hand_id)
+            .map(|(_, blind_seats)| *blind_seats)
+    }
+
+    /// The hand `hand_id` as PokerStars hand history text.
+    pub fn hand_history(&self, hand_id: &str) -> Option<String> {
+        export_pokerstars(self.until_hand_end(hand_id)?, self.blind_seats(hand_id))
+    }
+
+    /// The last hand as PokerStars hand history text.
+    pub fn last_hand_history(&self) -> Option<String> {
+        let hand_id = self.last_hand_id()?;
+        export_pokerstars(&self.actions, self.blind_seats(hand_id))
+    }
+
+    fn last_hand_id(&self) -> Option<&str> {
+        self.actions.iter().rev().find_map(|action| match action {
+            Action::GameHandIdSet(hand_id) => Some(hand_id.as_str()),
+            _ => None,
+        })
+    }
+
+    fn set_blind_seats(&mut self, blind_seats: Option<BlindSeats>) {
+        let Some(hand_id) = self.last_hand_id().map(str::to_string) else {
+            return;
+        };
+        self.blind_seats
+            .retain(|(blind_seats_hand_id, _)| *blind_seats_hand_id != hand_id);
+        if let Some(blind_seats) = blind_seats {
+            self.blind_seats.push((hand_id, blind_seats));
+        }
+    }
+
+    /// Before a new hand, the hands older than the current one become setup actions.
+    fn trim(&mut self) {
+        let hand_starts = self
+            .actions
+            .iter()
+            .enumerate()
+            .filter(|(_, action)| matches!(action, Action::GameHandIdSet(_)))
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        if hand_starts.len() < KEPT_HANDS {
+            return;
+        }
+
+        let kept_start = hand_starts[hand_starts.len() + 1 - KEPT_HANDS];
+        let mut actions = HandWriter::replay(&self.actions[..kept_start])
+            .next_hand()
+            .setup();
+        actions.extend(self.actions.drain(kept_start..));
+        self.actions = actions;
+
+        let kept_hand_ids = self
+            .actions
+            .iter()
+            .filter_map(|action| match action {
+                Action::GameHandIdSet(hand_id) => Some(hand_id.clone()),
+                _ => None,
+            })
+            .collect::<HashSet<_>>();
+        self.blind_seats
+            .retain(|(hand_id, _)| kept_hand_ids.contains(hand_id));
+    }
+}
+
+/// Runs after the tracker to pair each hand with the button and blinds it settled on.
+pub fn record_action_journal_system(
+    event_source: Res<Events<Action>>,
+    mut event_reader: Local<Option<ManualEventReader<Action>>>,
+    tracker_state: Res<TrackerState>,
+    mut action_journal: ResMut<ActionJournal>,
+) {
+    let event_reader = event_reader.get_or_insert_with(|| event_source.get_reader());
+    if event_reader.is_empty(&event_source) {
+        return;
+    }
+    event_reader
+        .iter(&event_source)
+        .for_each(|action| action_journal.push(action.clone()));
+    action_journal.set_blind_seats(tracker_state.blind_seats);
+}
+
+/// Writes the last hand of the journal as PokerStars hand history text.
+/// Hands before it are replayed only to know seats, stacks and game settings.
+/// `blind_seats` are the tracked ones, the journal alone may not name the button.
+pub fn export_pokerstars(journal: &[Action], blind_seats: Option<BlindSeats>) -> Option<String> {
+    let hand_start = journal
+        .iter()
+        .rposition(|action| matches!(action, Action::GameHandIdSet(_)))?;
+
+    let mut hand_writer = HandWriter::replay(&journal[..hand_start]).next_hand();
+    hand_writer.blind_seats = blind_seats;
+    journal[hand_start..]
+        .iter()
+        .for_each(|action| hand_writer.apply(action));
+
+    Some(hand_writer.finish())
+}
+
+#[derive(Default)]
+struct HandWriter {
+    hand_id: String,
+    tournament: Option<TournamentStartedParams>,
+    table_name: Option<String>,
+    game_type: Option<GameType>,
+    game_variant: GameVariant,
+    game_limit: Option<GameLimit>,
+    rake_rules: Option<RakeRules>,
+    max_seats: Option<u8>,
+    dealer_seat_num: Option<u8>,
+    blind_seats: Option<BlindSeats>,
+    seats: BTreeMap<u8, String>,
+    npcs: HashSet<String>,
+    stacks: HashMap<String, u64>,
+    sitting_out: HashSet<String>,
+    started: bool,
+    /// Seats, stacks and settings changed during the hand, for the next one.
+    later: Vec<Action>,
+    players_dealt: usize,
+    lines: Vec<String>,
+    board: Vec<Card>,
+    blinds: Vec<(u8, &'static str)>,
+    street_bets: HashMap<u8, u64>,
+    hand_bets: HashMap<u8, u64>,
+    street_max_bet: u64,
+    total_pot: u64,
+    folded: HashMap<u8, &'static str>,
+    collected: HashMap<String, u64>,
+    hand_rake: Option<HandRake>,
+}
+
+impl HandWriter {
+    /// Replays whole hands, only the table they leave matters.
+    fn replay(journal: &[Action]) -> Self {
+        journal
+            .iter()
+            .fold(HandWriter::default(), |hand_writer, action| {
+                let mut hand_writer = if matches!(action, Action::GameHandIdSet(_)) {
+                    hand_writer.next_hand()
+                } else {
+                    hand_writer
+                };
+                hand_writer.apply(action);
+                hand_writer
+            })
+    }
+
+    /// Writer of the next hand, starting from the seats, stacks and settings this one left.
+    fn next_hand(self) -> Self {
+        let mut hand_writer = HandWriter {
+            tournament: self.tournament,
+            table_name: self.table_name,
+            game_type: self.game_type,
+            game_variant: self.game_variant,
+            game_limit: self.game_limit,
+            rake_rules: self.rake_rules,
+            max_seats: self.max_seats,
+            dealer_seat_num: self.dealer_seat_num,
+            seats: self.seats,
+            npcs: self.npcs,
+            stacks: self.stacks,
+            sitting_out: self.sitting_out,
+            ..default()
+        };
+        self.later
+            .iter()
+            .for_each(|action| hand_writer.apply_setup(action));
+
+        hand_writer
+    }
+
+    /// Actions setting up the table of this writer from scratch.
+    fn setup(&self) -> Vec<Action> {
+        let seats = self.seats.iter().map(|(seat_num, name)| {
+            Action::SeatUpdated(SeatUpdatedParams {
+                name: name.clone(),
+                seat_num: *seat_num,
+                npc: self.npcs.contains(name),
+            })
+        });
+        let stacks = self.seats.values().filter_map(|name| {
+            self.stacks.get(name).map(|stack| {
+                Action::StackUpdated(StackUpdatedParams {
+                    name: name.clone(),
+                    stack: *stack,
+                })
+            })
+        });
+        let sitting_out = self
+            .seats
+            .values()
+            .filter(|name| self.sitting_out.contains(*name))
+            .map(|name| Action::PlayerSatOut(PlayerParams { name: name.clone() }));
+        let settings = [
+            self.tournament.clone().map(Action::TournamentStarted),
+            self.table_name.clone().map(Action::GameTableNameSet),
+            self.game_type.map(Action::GameTypeSet),
+            Some(Action::GameVariantSet(self.game_variant)),
+            self.game_limit.map(Action::GameLimitSet),
+            self.rake_rules.clone().map(Action::RakeRulesSet),
+            self.max_seats.map(Action::GameMaxSeatsSet),
+            self.dealer_seat_num.map(Action::GameDealerSeatNumSet),
+        ];
+
+        seats
+            .chain(stacks)
+            .chain(sitting_out)
+            .chain(settings.into_iter().flatten())
+            .collect()
+    }
+
+    fn apply_setup(&mut self, action: &Action) {
+        match action {
+            Action::SeatUpdated(SeatUpdatedParams {
+                name,
+                seat_num,
+                npc,
+            }) => {
+                self.seats.insert(*seat_num, name.clone());
+                if *npc {
+                    self.npcs.insert(name.clone());
+                } else {
+                    self.npcs.remove(name);
+                }
+            }
+            Action::StackUpdated(StackUpdatedParams { name, stack }) => {
+                self.stacks.insert(name.clone(), *stack);
+            }
+            Action::PlayerSatOut(PlayerParams { name }) => {
+                self.sitting_out.insert(name.clone());
+            }
+            Action::PlayerSatIn(PlayerParams { name }) => {
+                self.sitting_out.remove(name);
+            }
+            Action::PlayerLeft(PlayerParams { name }) => {
+                self.seats.retain(|_, seated_name| seated_name != name);
+                self.stacks.remove(name);
+                self.sitting_out.remove(name);
+                self.npcs.remove(name);
+            }
+            Action::TournamentStarted(tournament) => self.tournament = Some(tournament.clone()),
+            Action::GameHandIdSet(hand_id) => self.hand_id = hand_id.clone(),
+            Action::GameTypeSet(game_type) => self.game_type = Some(*game_type),
+            Action::GameVariantSet(game_variant) => self.game_variant = *game_variant,
+            Action::GameLimitSet(game_limit) => self.game_limit = Some(*game_limit),
+            Action::RakeRulesSet(rake_rules) => self.rake_rules = Some(rake_rules.clone()),
+            Action::GameMaxSeatsSet(max_seats) => self.max_seats = Some(*max_seats),
+            Action::GameTableNameSet(table_name) => self.table_name = Some(table_name.clone()),
+            Action::GameDealerSeatNumSet(dealer_seat_num) => {
+                self.dealer_seat_num = Some(*dealer_seat_num)
+            }
+            _ => (),
+        }
+    }
+
+    fn apply(&mut self, action: &Action) {
+        match action {
+            Action::NpcCardsDealt(NpcCardsDealtParams { name, cards }) => {
+                self.start();
+                self.hole_cards();
+                self.lines
+                    .push(format!("Dealt to {name} [{}]", cards_line(cards)));
+            }
+            Action::CommunityCardsDealt(CommunityCardsDealtParams {
+                prev_cards,
+                new_cards,
+            }) => {
+                self.start();
+                self.hole_cards();
+                self.return_uncalled_bet();
+                self.board = prev_cards.clone();
+                self.board.extend(new_cards.iter().copied());
+                self.street_bets.clear();
+                self.street_max_bet = 0;
+
+                let street_line = match self.board.len() {
+                    3 => format!("*** FLOP *** [{}]", cards_line(&self.board)),
+                    4 => format!(
+                        "*** TURN *** [{}] [{}]",
+                        cards_line(&self.board[..3]),
+                        self.board[3]
+                    ),
+                    _ => format!(
+                        "*** RIVER *** [{}] [{}]",
+                        cards_line(&self.board[..self.board.len() - 1]),
+                        self.board[self.board.len() - 1]
+                    ),
+                };
+                self.lines.push(street_line);
+            }
+            Action::AntePosted(AntePostedParams {
+                seat_index: seat_num,
+                ante,
+            }) => {
+                self.start();
+                self.ante(*seat_num, *ante);
+            }
+            Action::BetMade(BetMadeParams {
+                seat_index: seat_num,
+                bet_size,
+            }) => {
+                self.start();
+                self.bet(*seat_num, *bet_size);
+            }
+            Action::PotAwarded(PotAwardedParams { pots }) => {
+                self.start();
+                self.return_uncalled_bet();
+                self.award(pots);
+            }
+            Action::PlayerSatOut(PlayerParams { name }) if self.started => {
+                self.sitting_out.insert(name.clone());
+                self.lines.push(format!("{name}: sits out"));
+            }
+            Action::PlayerSatIn(PlayerParams { name }) if self.started => {
+                self.sitting_out.remove(name);
+                self.lines.push(format!("{name} has returned"));
+            }
+            Action::PlayerLeft(PlayerParams { name }) if self.started => {
+                self.lines.push(format!("{name} leaves the table"));
+                self.later.push(action.clone());
+            }
+            _ if self.started => self.later.push(action.clone()),
+            _ => self.apply_setup(action),
+        }
+    }
+
+    fn start(&mut self) {
+        if self.started {
+            return;
+        }
+        self.started = true;
+        self.players_dealt = self
+            .seats
+            .values()
+            .filter(|name| {
+                self.stacks.get(*name).is_some_and(|stack| *stack > 0)
+                    && !self.sitting_out.contains(*name)
+            })
+            .count();
+
+        self.lines.push(format!(
+            "PokerStars Hand #{}:  {}{} ({})",
+            self.hand_id,
+            self.tournament
+                .as_ref()
+                .map_or_else(String::new, |tournament| format!(
+                    "Tournament #{}, ",
+                    tournament.tournament_id
+                )),
+            game_name(self.game_variant, self.game_type),
+            self.game_limit
+                .map_or_else(|| "-".to_string(), |game_limit| game_limit.to_string()),
+        ));
+        self.lines.push(format!(
+            "Table '{}' {}-max Seat #{} is the button",
+            self.table_name.as_deref().unwrap_or("Unknown"),
+            self.max_seats.unwrap_or(self.seats.len() as u8),
+            self.button().unwrap_or_default(),
+        ));
+        let seat_lines = self
+            .seats
+            .iter()
+            .map(|(seat_num, name)| {
+                let seat_line = format!(
+                    "Seat {seat_num}: {name} ({} in chips)",
+                    self.amount(self.stacks.get(name).copied().unwrap_or_default())
+                );
+                if self.sitting_out.contains(name) {
+                    seat_line + " is sitting out"
+                } else {
+                    seat_line
+                }
+            })
+            .collect::<Vec<_>>();
+        self.lines.extend(seat_lines);
+    }
+
+    /// Amounts in the currency of the stakes, plain chips when they are unknown.
+    fn amount(&self, amount: u64) -> String {
+        self.game_limit.map_or_else(
+            || amount.to_string(),
+            |game_limit| game_limit.format_amount(amount),
+        )
+    }
+
+    /// Tracked button first, the journal only knows the dealer seats it was told.
+    fn button(&self) -> Option<u8> {
+        self.blind_seats
+            .map(|blind_seats| blind_seats.button)
+            .or(self.dealer_seat_num)
+    }
+
+    /// Blinds still to post in order. Without tracked blind seats
+    /// the first two bets of the hand are taken for them.
+    fn blinds_to_post(&self) -> Vec<(Option<u8>, &'static str)> {
+        let blinds = match self.blind_seats {
+            Some(blind_seats) if blind_seats.dead_small_blind => {
+                vec![(Some(blind_seats.big_blind), "big")]
+            }
+            Some(blind_seats) => vec![
+                (Some(blind_seats.small_blind), "small"),
+                (Some(blind_seats.big_blind), "big"),
+            ],
+            None => vec![(None, "small"), (None, "big")],
+        };
+
+        blinds.into_iter().skip(self.blinds.len()).collect()
+    }
+
+    fn hole_cards(&mut self) {
+        if !self.lines.iter().any(|line| line == "*** HOLE CARDS ***") {
+            self.lines.push("*** HOLE CARDS ***".to_string());
+        }
+    }
+
+    fn seat_name(&self, seat_num: u8) -> String {
+        self.seats
+            .get(&seat_num)
+            .cloned()
+            .unwrap_or_else(|| format!("Seat {seat_num}"))
+    }
+
+    /// Antes go to the pot, but not to the bets of the street.
+    fn ante(&mut self, seat_num: u8, ante: u64) {
+        let name = self.seat_name(seat_num);
+        let stack = self.stacks.entry(name.clone()).or_default();
+        *stack = stack.saturating_sub(ante);
+        let is_all_in = *stack == 0;
+        self.total_pot += ante;
+
+        let line = format!("{name}: posts the ante {}", self.amount(ante));
+        if is_all_in {
+            self.lines.push(format!("{line} and is all-in"));
+        } else {
+            self.lines.push(line);
+        }
+    }
+
+    fn bet(&mut self, seat_num: u8, bet_size: u64) {
+        let name = self.seat_name(seat_num);
+        let stack = self.stacks.entry(name.clone()).or_default();
+        *stack = stack.saturating_sub(bet_size);
+        let is_all_in = *stack == 0 && bet_size > 0;
+
+        let prev_street_bet = self.street_bets.get(&seat_num).copied().unwrap_or_default();
+        let street_bet = prev_street_bet + bet_size;
+        self.street_bets.insert(seat_num, street_bet);
+        *self.hand_bets.entry(seat_num).or_default() += bet_size;
+        self.total_pot += bet_size;
+
+        let blind_name = self
+            .blinds_to_post()
+            .first()
+            .filter(|(blind_seat_num, _)| blind_seat_num.unwrap_or(seat_num) == seat_num)
+            .map(|(_, blind_name)| *blind_name)
+            .filter(|_| self.board.is_empty() && bet_size > 0);
+        let line = if let Some(blind_name) = blind_name {
+            self.blinds.push((seat_num, blind_name));
+            format!("{name}: posts {blind_name} blind {}", self.amount(bet_size))
+        } else if bet_size == 0 && prev_street_bet < self.street_max_bet {
+            self.folded.insert(seat_num, street_name(self.board.len()));
+            format!("{name}: folds")
+        } else if bet_size == 0 {
+            format!("{name}: checks")
+        } else if street_bet > self.street_max_bet && self.street_max_bet == 0 {
+            format!("{name}: bets {}", self.amount(bet_size))
+        } else if street_bet > self.street_max_bet {
+            format!(
+                "{name}: raises {} to {}",
+                self.amount(street_bet - self.street_max_bet),
+                self.amount(street_bet)
+            )
+        } else {
+            format!("{name}: calls {}", self.amount(bet_size))
+        };
+        self.street_max_bet = self.street_max_bet.max(street_bet);
+
+        if is_all_in {
+            self.lines.push(format!("{line} and is all-in"));
+        } else {
+            self.lines.push(line);
+        }
+
+        if blind_name.is_some() && self.blinds_to_post().is_empty() {
+            self.hole_cards();
+        }
+    }
+
+    /// The part of the biggest bet nobody matched goes back before the next street
+    /// or the showdown, as the tracker does when the street closes.
+    fn return_uncalled_bet(&mut self) {
+        let mut hand_bets = self
+            .hand_bets
+            .iter()
+            .map(|(seat_num, bets_sum)| (*seat_num, *bets_sum))
+            .collect::<Vec<_>>();
+        hand_bets.sort_by_key(|(_, bets_sum)| Reverse(*bets_sum));
+        let Some(&(seat_num, bets_sum)) = hand_bets.first() else {
+            return;
+        };
+        let matched = hand_bets.get(1).map_or(0, |(_, bets_sum)| *bets_sum);
+        let amount = bets_sum - matched;
+        if amount == 0 {
+            return;
+        }
+
+        let name = self.seat_name(seat_num);
+        self.hand_bets.insert(seat_num, matched);
+        self.total_pot -= amount;
+        *self.stacks.entry(name.clone()).or_default() += amount;
+        self.lines.push(format!(
+            "Uncalled bet ({}) returned to {name}",
+            self.amount(amount)
+        ));
+    }
+
+    /// Same rake as the tracker: by the table rules when they agree with the amounts.
+    fn award(&mut self, pots: &[PotShare]) {
+        let awarded = pots.iter().map(|pot_share| pot_share.amount).sum();
+        let rules_rake = self.rake_rules.as_ref().map(|rake_rules| {
+            rake_rules.rake(self.total_pot, self.players_dealt, self.board.len() >= 3)
+        });
+
+        for PotShare { name, amount } in pots {
+            *self.stacks.entry(name.clone()).or_default() += amount;
+            *self.collected.entry(name.clone()).or_default() += amount;
+            self.lines.push(format!(
+                "{name} collected {} from pot",
+                self.amount(*amount)
+            ));
+        }
+        self.hand_rake = Some(HandRake::of_award(self.total_pot, awarded, rules_rake));
+    }
+
+    fn finish(mut self) -> String {
+        self.start();
+
+        self.lines.push("*** SUMMARY ***".to_string());
+        self.lines.push(format!(
+            "Total pot {} | Rake {}",
+            self.amount(self.total_pot),
+            self.amount(self.hand_rake.map_or(0, |hand_rake| hand_rake.total()))
+        ));
+        if !self.board.is_empty() {
+            self.lines
+                .push(format!("Board [{}]", cards_line(&self.board)));
+        }
+
+        let blind_seat = |blind_name| {
+            self.blinds
+                .iter()
+                .find(|(_, posted_blind_name)| *posted_blind_name == blind_name)
+                .map(|(seat_num, _)| *seat_num)
+        };
+        let small_blind_seat = blind_seat("small");
+        let big_blind_seat = blind_seat("big");
+        let button = self.button();
+        let summary_lines = self
+            .seats
+            .iter()
+            .map(|(seat_num, name)| {
+                let mut summary_line = format!("Seat {seat_num}: {name}");
+                if Some(*seat_num) == button {
+                    summary_line.push_str(" (button)");
+                }
+                if Some(*seat_num) == small_blind_seat {
+                    summary_line.push_str(" (small blind)");
+                }
+                if Some(*seat_num) == big_blind_seat {
+                    summary_line.push_str(" (big blind)");
+                }
+                if let Some(street) = self.folded.get(seat_num) {
+                    let _ = write!(summary_line, " folded {street}");
+                }
+                if let Some(collected) = self.collected.get(name) {
+                    let _ = write!(summary_line, " collected ({})", self.amount(*collected));
+                }
+                summary_line
+            })
+            .collect::<Vec<_>>();
+        self.lines.extend(summary_lines);
+
+        self.lines.join("\n") + "\n"
+    }
+}
+
+fn game_name(game_variant: GameVariant, game_type: Option<GameType>) -> String {
+    let betting = match game_type {
+        Some(GameType::NL) => "No Limit",
+        Some(GameType::PL) => "Pot Limit",
+        Some(GameType::FL) => "Limit",
+        None => "Unknown Limit",
+    };
+
+    format!("{} {betting}", game_variant.name())
+}
+
+fn street_name(board_len: usize) -> &'static str {
+    match board_len {
+        0..=2 => "before Flop",
+        3 => "on the Flop",
+        4 => "on the Turn",
+        _ => "on the River",
+    }
+}
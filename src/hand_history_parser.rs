@@ -0,0 +1,450 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+use crate::action::*;
+use crate::components::{
+    BlindLevel, BlindSchedule, Currency, GameLimit, GameType, GameVariant, LevelProgression,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "hand history line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Turns PokerStars hand history text into `Action` batches, one hand at a time.
+/// Remembers who is seated, so players are spawned only once per table
+/// and those gone from the seat list leave it.
+#[derive(Debug, Default)]
+pub struct HandHistoryParser {
+    seats: HashMap<u8, String>,
+    sitting_out: HashSet<String>,
+    /// Started by the first hand of the tournament.
+    tournament_id: Option<String>,
+}
+
+impl HandHistoryParser {
+    /// Every batch is meant for its own `app.update()`: seats must exist before
+    /// the dealer is set, stacks before the first bet and so on.
+    pub fn parse_hand(&mut self, hand_text: &str) -> Result<Vec<Vec<Action>>, ParseError> {
+        let mut lines = hand_text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty());
+
+        let (line_num, header) = lines.next().ok_or_else(|| ParseError {
+            line: 0,
+            message: "empty hand".into(),
+        })?;
+        let hand_id = header
+            .strip_prefix("PokerStars Hand #")
+            .and_then(|rest| rest.split(':').next())
+            .ok_or_else(|| parse_error(line_num, "expected PokerStars hand header"))?;
+        let tournament_id = header
+            .split_once("Tournament #")
+            .and_then(|(_, rest)| rest.split([',', ' ']).next())
+            .filter(|tournament_id| !tournament_id.is_empty());
+        let game_variant = if header.contains("6+ Hold'em") || header.contains("Short Deck") {
+            GameVariant::ShortDeck
+        } else if header.contains("5 Card Omaha") {
+            GameVariant::Omaha5
+        } else if header.contains("Omaha") {
+            GameVariant::Omaha
+        } else if header.contains("Hold'em") {
+            GameVariant::Holdem
+        } else {
+            return Err(parse_error(line_num, "expected game variant"));
+        };
+        let game_type = if header.contains("No Limit") {
+            GameType::NL
+        } else if header.contains("Pot Limit") {
+            GameType::PL
+        } else if header.contains(" Limit") {
+            GameType::FL
+        } else {
+            return Err(parse_error(line_num, "expected betting structure"));
+        };
+        let ante = hand_text
+            .lines()
+            .find_map(|line| line.split_once(": posts the ante "))
+            .and_then(|(_, ante)| ante.split_whitespace().next())
+            .and_then(parse_amount)
+            .unwrap_or_default();
+        let game_limit = parse_game_limit(header)
+            .ok_or_else(|| parse_error(line_num, "expected stakes"))?
+            .with_ante(ante);
+
+        let (line_num, table) = lines
+            .next()
+            .ok_or_else(|| parse_error(line_num, "expected table line"))?;
+        let max_seats = table
+            .split_whitespace()
+            .find_map(|word| word.strip_suffix("-max"))
+            .and_then(|max_seats| max_seats.parse::<u8>().ok())
+            .ok_or_else(|| parse_error(line_num, "expected max seats"))?;
+        let table_name = table
+            .strip_prefix("Table '")
+            .and_then(|rest| rest.split_once('\''))
+            .map(|(table_name, _)| table_name)
+            .ok_or_else(|| parse_error(line_num, "expected table name"))?;
+        let dealer_seat_num = table
+            .split_once("Seat #")
+            .and_then(|(_, rest)| rest.split_whitespace().next())
+            .and_then(|seat_num| seat_num.parse::<u8>().ok())
+            .ok_or_else(|| parse_error(line_num, "expected button seat"))?;
+
+        let hero_name = hand_text.lines().find_map(|line| {
+            line.trim()
+                .strip_prefix("Dealt to ")
+                .and_then(|rest| rest.rsplit_once(" ["))
+                .map(|(name, _)| name.to_string())
+        });
+
+        let mut seats = BTreeMap::new();
+        let mut sitting_out = HashSet::new();
+        let mut stacks = Vec::new();
+        let mut lines = lines.peekable();
+        while let Some((line_num, line)) = lines.next_if(|(_, line)| line.starts_with("Seat ")) {
+            let (seat_num, rest) = line["Seat ".len()..]
+                .split_once(": ")
+                .ok_or_else(|| parse_error(line_num, "expected seat line"))?;
+            let seat_num = seat_num
+                .parse::<u8>()
+                .map_err(|_| parse_error(line_num, "expected seat number"))?;
+            let (name, rest) = rest
+                .rsplit_once(" (")
+                .ok_or_else(|| parse_error(line_num, "expected stack"))?;
+            let stack = rest
+                .split_whitespace()
+                .next()
+                .and_then(parse_amount)
+                .ok_or_else(|| parse_error(line_num, "expected stack amount"))?;
+
+            seats.insert(seat_num, name.to_string());
+            if rest.ends_with(" is sitting out") {
+                sitting_out.insert(name.to_string());
+            }
+            stacks.push(Action::StackUpdated(StackUpdatedParams {
+                name: name.to_string(),
+                stack,
+            }));
+        }
+
+        let mut batches = vec![];
+        let left_seats = self
+            .seats
+            .iter()
+            .filter(|(seat_num, name)| seats.get(seat_num) != Some(name))
+            .map(|(_, name)| Action::PlayerLeft(PlayerParams { name: name.clone() }))
+            .collect::<Vec<_>>();
+        if !left_seats.is_empty() {
+            batches.push(left_seats);
+        }
+        self.seats
+            .retain(|seat_num, name| seats.get(seat_num) == Some(name));
+        self.sitting_out
+            .retain(|name| self.seats.values().any(|seated_name| seated_name == name));
+
+        let new_seats = seats
+            .iter()
+            .filter(|(seat_num, name)| self.seats.get(seat_num) != Some(name))
+            .map(|(seat_num, name)| {
+                Action::SeatUpdated(SeatUpdatedParams {
+                    name: name.clone(),
+                    seat_num: *seat_num,
+                    npc: hero_name.as_ref() == Some(name),
+                })
+            })
+            .collect::<Vec<_>>();
+        if !new_seats.is_empty() {
+            batches.push(new_seats);
+        }
+        self.seats.extend(
+            seats
+                .iter()
+                .map(|(seat_num, name)| (*seat_num, name.clone())),
+        );
+
+        if let Some(tournament_id) = tournament_id
+            .filter(|tournament_id| self.tournament_id.as_deref() != Some(tournament_id))
+        {
+            // The blind schedule is not in the hand history, every hand sets its own stakes
+            batches.push(vec![Action::TournamentStarted(TournamentStartedParams {
+                tournament_id: tournament_id.to_string(),
+                schedule: BlindSchedule {
+                    levels: vec![BlindLevel {
+                        small_blind: game_limit.small_blind,
+                        big_blind: game_limit.big_blind,
+                        ante: game_limit.ante,
+                    }],
+                    progression: LevelProgression::Hands(1),
+                },
+                payouts: vec![],
+            })]);
+            self.tournament_id = Some(tournament_id.to_string());
+        }
+        batches.push(vec![
+            Action::GameHandIdSet(hand_id.to_string()),
+            Action::GameTypeSet(game_type),
+            Action::GameVariantSet(game_variant),
+            Action::GameLimitSet(game_limit),
+        ]);
+        batches.push(vec![
+            Action::GameMaxSeatsSet(max_seats),
+            Action::GameTableNameSet(table_name.to_string()),
+            Action::GameDealerSeatNumSet(dealer_seat_num),
+        ]);
+        stacks.extend(
+            sitting_out
+                .difference(&self.sitting_out)
+                .map(|name| Action::PlayerSatOut(PlayerParams { name: name.clone() })),
+        );
+        stacks.extend(
+            self.sitting_out
+                .difference(&sitting_out)
+                .map(|name| Action::PlayerSatIn(PlayerParams { name: name.clone() })),
+        );
+        self.sitting_out = sitting_out;
+        batches.push(stacks);
+
+        let seat_by_name = seats
+            .iter()
+            .map(|(seat_num, name)| (name.as_str(), *seat_num))
+            .collect::<HashMap<_, _>>();
+        let mut street_bets = HashMap::<u8, u64>::new();
+        let mut board = Vec::new();
+        let mut pots = Vec::<PotShare>::new();
+
+        for (line_num, line) in lines {
+            if line.starts_with("*** SUMMARY ***") {
+                break;
+            }
+
+            if let Some(street) = line.strip_prefix("*** ") {
+                if !(street.starts_with("FLOP")
+                    || street.starts_with("TURN")
+                    || street.starts_with("RIVER"))
+                {
+                    continue;
+                }
+
+                let street_cards = line
+                    .rsplit_once('[')
+                    .map(|(_, cards)| cards.trim_end_matches(']'))
+                    .ok_or_else(|| parse_error(line_num, "expected street cards"))?;
+                let new_cards = parse_cards(street_cards)
+                    .ok_or_else(|| parse_error(line_num, "expected cards"))?;
+                batches.push(vec![Action::CommunityCardsDealt(
+                    CommunityCardsDealtParams {
+                        prev_cards: board.clone(),
+                        new_cards: new_cards.clone(),
+                    },
+                )]);
+                board.extend(new_cards);
+                street_bets.clear();
+                continue;
+            }
+
+            if let Some(rest) = line.strip_prefix("Dealt to ") {
+                let (name, cards) = rest
+                    .rsplit_once(" [")
+                    .ok_or_else(|| parse_error(line_num, "expected dealt cards"))?;
+                let cards = parse_cards(cards.trim_end_matches(']'))
+                    .ok_or_else(|| parse_error(line_num, "expected cards"))?;
+                batches.push(vec![Action::NpcCardsDealt(NpcCardsDealtParams {
+                    name: name.to_string(),
+                    cards,
+                })]);
+                continue;
+            }
+
+            if let Some(action) = parse_table_move(line) {
+                match &action {
+                    Action::PlayerSatOut(PlayerParams { name }) => {
+                        self.sitting_out.insert(name.clone());
+                    }
+                    Action::PlayerSatIn(PlayerParams { name }) => {
+                        self.sitting_out.remove(name);
+                    }
+                    Action::PlayerLeft(PlayerParams { name }) => {
+                        self.seats.retain(|_, seated_name| seated_name != name);
+                        self.sitting_out.remove(name);
+                    }
+                    _ => (),
+                }
+                batches.push(vec![action]);
+                continue;
+            }
+
+            // Tournament bust outs, e.g. `Bob finished the tournament in 3rd place`
+            if let Some((name, _)) = line.split_once(" finished the tournament") {
+                if seat_by_name.contains_key(name) {
+                    batches.push(vec![Action::StackUpdated(StackUpdatedParams {
+                        name: name.to_string(),
+                        stack: 0,
+                    })]);
+                }
+                continue;
+            }
+
+            // Pot winners, e.g. `Hero collected $1.90 from pot` or `from side pot-1`
+            if let Some((name, collected)) = line.split_once(" collected ") {
+                if seat_by_name.contains_key(name) {
+                    let amount = collected
+                        .split_whitespace()
+                        .next()
+                        .and_then(parse_amount)
+                        .ok_or_else(|| parse_error(line_num, "expected collected amount"))?;
+                    pots.push(PotShare {
+                        name: name.to_string(),
+                        amount,
+                    });
+                }
+                continue;
+            }
+
+            let Some((name, player_action)) = line.split_once(": ") else {
+                continue;
+            };
+            let Some(&seat_num) = seat_by_name.get(name) else {
+                continue;
+            };
+            if let Some(ante) = player_action.strip_prefix("posts the ante ") {
+                let ante = ante
+                    .split_whitespace()
+                    .next()
+                    .and_then(parse_amount)
+                    .ok_or_else(|| parse_error(line_num, "expected ante amount"))?;
+                batches.push(vec![Action::AntePosted(AntePostedParams {
+                    seat_index: seat_num,
+                    ante,
+                })]);
+                continue;
+            }
+            let street_bet = street_bets.entry(seat_num).or_default();
+            let mut words = player_action.split_whitespace();
+
+            let bet_size = match words.next() {
+                Some("folds") | Some("checks") => 0,
+                Some("calls") | Some("bets") => words
+                    .next()
+                    .and_then(parse_amount)
+                    .ok_or_else(|| parse_error(line_num, "expected bet amount"))?,
+                Some("raises") => {
+                    let raise_to = words
+                        .nth(2)
+                        .and_then(parse_amount)
+                        .ok_or_else(|| parse_error(line_num, "expected raise amount"))?;
+                    raise_to.saturating_sub(*street_bet)
+                }
+                Some("posts") => words
+                    .filter_map(parse_amount)
+                    .next_back()
+                    .ok_or_else(|| parse_error(line_num, "expected blind amount"))?,
+                _ => continue,
+            };
+            *street_bet += bet_size;
+
+            batches.push(vec![Action::BetMade(BetMadeParams {
+                seat_index: seat_num,
+                bet_size,
+            })]);
+        }
+
+        if !pots.is_empty() {
+            batches.push(vec![Action::PotAwarded(PotAwardedParams { pots })]);
+        }
+
+        Ok(batches)
+    }
+}
+
+/// Splits off the hands whose `*** SUMMARY ***` section is already finished
+/// by a blank line. The unfinished tail stays in `buffer`.
+pub fn take_complete_hands(buffer: &mut String) -> Vec<String> {
+    let mut hands = vec![];
+    while let Some(summary_index) = buffer.find("*** SUMMARY ***") {
+        let Some(blank_line_index) = buffer[summary_index..].find("\n\n") else {
+            break;
+        };
+
+        let hand_end = summary_index + blank_line_index;
+        let hand = buffer[..hand_end]
+            .trim_matches(|char: char| char.is_whitespace() || char == '\u{feff}')
+            .to_string();
+        buffer.drain(..hand_end);
+        hands.push(hand);
+    }
+
+    hands
+}
+
+/// Chips as integer, money amounts in cents: `1500`, `$0.50`, `€2`.
+fn parse_amount(amount: &str) -> Option<u64> {
+    // Thousands separators, e.g. `$1,234.56` or `12,500`
+    let amount = amount.replace(',', "");
+    let money_amount = amount.trim_start_matches(['$', '€', '£']);
+    if money_amount.len() == amount.len() {
+        return amount.parse().ok();
+    }
+
+    let (whole, cents) = money_amount.split_once('.').unwrap_or((money_amount, "0"));
+    let cents = format!("{cents:0<2}");
+    Some(whole.parse::<u64>().ok()? * 100 + cents.get(..2)?.parse::<u64>().ok()?)
+}
+
+/// Blinds in the first parentheses holding them: `($0.50/$1.00 USD)` or `Level I (10/20)`.
+fn parse_game_limit(header: &str) -> Option<GameLimit> {
+    header.split('(').skip(1).find_map(|rest| {
+        let (small_blind, big_blind) = rest.split([')', ' ']).next()?.split_once('/')?;
+        let currency = big_blind
+            .chars()
+            .next()
+            .and_then(Currency::from_symbol)
+            .unwrap_or_default();
+
+        Some(GameLimit::new(
+            parse_amount(small_blind)?,
+            parse_amount(big_blind)?,
+            currency,
+        ))
+    })
+}
+
+/// `Bob: sits out`, `Bob is sitting out`, `Bob has returned` or `Bob leaves the table`.
+fn parse_table_move(line: &str) -> Option<Action> {
+    let (name, action): (_, fn(PlayerParams) -> Action) = if let Some(name) = line
+        .strip_suffix(": sits out")
+        .or_else(|| line.strip_suffix(" is sitting out"))
+    {
+        (name, Action::PlayerSatOut)
+    } else if let Some(name) = line.strip_suffix(" has returned") {
+        (name, Action::PlayerSatIn)
+    } else {
+        (line.strip_suffix(" leaves the table")?, Action::PlayerLeft)
+    };
+
+    Some(action(PlayerParams {
+        name: name.to_string(),
+    }))
+}
+
+fn parse_cards(cards: &str) -> Option<Vec<Card>> {
+    cards.split_whitespace().map(Card::parse).collect()
+}
+
+fn parse_error(line: usize, message: &str) -> ParseError {
+    ParseError {
+        line,
+        message: message.into(),
+    }
+}
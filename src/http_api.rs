@@ -0,0 +1,213 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::sync::mpsc::{self, Sender};
+
+use serde::Serialize;
+
+use crate::action::Action;
+use crate::server::{ServerCommand, TrackerServer};
+use crate::stats::PlayerStats;
+use crate::view::{Street, TableView};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryReply {
+    pub status: u16,
+    pub body: String,
+}
+
+impl QueryReply {
+    fn json(body: &impl Serialize) -> Self {
+        match serde_json::to_string(body) {
+            Ok(body) => QueryReply { status: 200, body },
+            Err(err) => QueryReply::error(500, &err.to_string()),
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        QueryReply {
+            status,
+            body: serde_json::json!({ "error": message }).to_string(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct TableSummary<'a> {
+    table: &'a str,
+    hand_id: Option<&'a str>,
+    street: Street,
+    pot: u64,
+    players: usize,
+}
+
+#[derive(Serialize)]
+struct TableDetails<'a> {
+    table: &'a str,
+    #[serde(flatten)]
+    view: TableView,
+}
+
+#[derive(Serialize)]
+struct HandDetails<'a> {
+    table: &'a str,
+    hand_id: &'a str,
+    actions: &'a [Action],
+    text: Option<String>,
+}
+
+impl TrackerServer {
+    /// Answers `GET` paths of the query API:
+    /// `/tables`, `/tables/{id}`, `/players/{name}/stats`, `/hands/{hand_id}`.
+    pub fn query(&self, path: &str) -> QueryReply {
+        let segments = path
+            .trim_matches('/')
+            .split('/')
+            .map(percent_decode)
+            .collect::<Vec<_>>();
+        let segments = segments.iter().map(String::as_str).collect::<Vec<_>>();
+
+        match segments.as_slice() {
+            ["tables"] => {
+                let mut tables = self
+                    .sessions
+                    .iter()
+                    .map(|(table, table_session)| {
+                        let table_view = table_session.table_view();
+                        (table.as_str(), table_view)
+                    })
+                    .collect::<Vec<_>>();
+                tables.sort_by_key(|(table, _)| *table);
+
+                QueryReply::json(
+                    &tables
+                        .iter()
+                        .map(|(table, table_view)| TableSummary {
+                            table,
+                            hand_id: table_view.hand_id.as_deref(),
+                            street: table_view.street,
+                            pot: table_view.pot,
+                            players: table_view.players.len(),
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            }
+            ["tables", table] => match self.sessions.get(*table) {
+                Some(table_session) => QueryReply::json(&TableDetails {
+                    table,
+                    view: table_session.table_view(),
+                }),
+                None => QueryReply::error(404, "unknown table"),
+            },
+            ["players", name, "stats"] => {
+                let mut player_stats = PlayerStats::new(*name);
+                self.sessions
+                    .values()
+                    .filter_map(|table_session| table_session.stats().player_stats(name))
+                    .for_each(|table_player_stats| player_stats.add(&table_player_stats));
+
+                if player_stats.hands == 0 {
+                    QueryReply::error(404, "unknown player")
+                } else {
+                    QueryReply::json(&player_stats)
+                }
+            }
+            ["hands", hand_id] => self
+                .sessions
+                .iter()
+                .find_map(|(table, table_session)| {
+                    let action_journal = table_session.journal();
+                    let journal = action_journal.until_hand_end(hand_id)?;
+                    let hand_start = journal
+                        .iter()
+                        .rposition(|action| matches!(action, Action::GameHandIdSet(_)))?;
+
+                    Some(QueryReply::json(&HandDetails {
+                        table,
+                        hand_id,
+                        actions: &journal[hand_start..],
+                        text: action_journal.hand_history(hand_id),
+                    }))
+                })
+                .unwrap_or_else(|| QueryReply::error(404, "unknown hand")),
+            _ => QueryReply::error(404, "unknown path"),
+        }
+    }
+}
+
+/// Minimal HTTP/1.1: one `GET` per connection, JSON answer, connection closed.
+pub(crate) fn handle_http_connection(
+    stream: TcpStream,
+    command_sender: Sender<ServerCommand>,
+) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Skip headers, requests have no body
+    let mut header_line = String::new();
+    while reader.read_line(&mut header_line)? > 0 && !header_line.trim().is_empty() {
+        header_line.clear();
+    }
+
+    let mut request_parts = request_line.split_whitespace();
+    let query_reply = match (request_parts.next(), request_parts.next()) {
+        (Some("GET"), Some(path)) => {
+            let path = path.split('?').next().unwrap_or_default().to_string();
+            let (reply_sender, reply_receiver) = mpsc::channel();
+            command_sender
+                .send(ServerCommand::Query(path, reply_sender))
+                .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "tracker stopped"))?;
+            reply_receiver
+                .recv()
+                .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "tracker stopped"))?
+        }
+        (Some(_), Some(_)) => QueryReply::error(405, "only GET is supported"),
+        _ => QueryReply::error(400, "bad request"),
+    };
+
+    write!(
+        writer,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        query_reply.status,
+        status_text(query_reply.status),
+        query_reply.body.len(),
+        query_reply.body
+    )?;
+    writer.flush()
+}
+
+fn status_text(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    }
+}
+
+/// Player names come percent-encoded in paths.
+fn percent_decode(segment: &str) -> String {
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let hex = bytes
+            .get(index + 1..index + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[index], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+
+/// Prize money each stack is worth by the Malmuth-Harville model: a player
+/// finishes first with the share of chips they hold, then the same among the rest.
+/// Empty stacks are worth nothing, `payouts` are listed from first place down.
+pub fn icm_equities(stacks: &[u64], payouts: &[u64]) -> Vec<f64> {
+    let players = stacks.len();
+    let mut equities = vec![0.0; players];
+    let total_chips = stacks.iter().sum::<u64>();
+    let places = payouts
+        .len()
+        .min(stacks.iter().filter(|stack| **stack > 0).count());
+    if total_chips == 0 || places == 0 {
+        return equities;
+    }
+
+    // Probability that exactly the players in the mask took the first places
+    let mut finished_probability = vec![0.0; 1 << players];
+    finished_probability[0] = 1.0;
+    for mask in 0..finished_probability.len() {
+        let place = mask.count_ones() as usize;
+        let probability = finished_probability[mask];
+        if place >= places || probability == 0.0 {
+            continue;
+        }
+
+        let chips_left = total_chips
+            - (0..players)
+                .filter(|player| mask & (1 << player) != 0)
+                .map(|player| stacks[player])
+                .sum::<u64>();
+        for player in (0..players).filter(|player| mask & (1 << player) == 0) {
+            let finish_probability = probability * stacks[player] as f64 / chips_left as f64;
+            equities[player] += finish_probability * payouts[place] as f64;
+            finished_probability[mask | (1 << player)] += finish_probability;
+        }
+    }
+
+    equities
+}
+
+/// What going all-in gains over folding, positive when the shove is better.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EvDelta {
+    pub chip_ev: f64,
+    /// Prize money by `icm_equities`.
+    pub dollar_ev: f64,
+}
+
+/// Compares folding with an all-in called by `caller`, won with `win_probability`.
+/// `stacks` are behind, `bets` already in the pot this hand, antes included. Folding or losing
+/// hands the whole pot to the caller, other players only lose their bets.
+pub fn all_in_delta(
+    stacks: &[u64],
+    bets: &[u64],
+    payouts: &[u64],
+    hero: usize,
+    caller: usize,
+    win_probability: f64,
+) -> EvDelta {
+    let hand_start = stacks
+        .iter()
+        .zip(bets)
+        .map(|(stack, bet)| stack + bet)
+        .collect::<Vec<_>>();
+    let pot = bets.iter().sum::<u64>();
+    let covered = hand_start[hero].min(hand_start[caller]);
+    let dead_money = pot - bets[hero] - bets[caller];
+
+    let stacks_after = |hero_chips: u64, caller_chips: u64| {
+        let mut stacks_after = stacks.to_vec();
+        stacks_after[hero] = hero_chips;
+        stacks_after[caller] = caller_chips;
+        stacks_after
+    };
+    let fold = stacks_after(stacks[hero], stacks[caller] + pot);
+    let win = stacks_after(
+        hand_start[hero] + covered + dead_money,
+        hand_start[caller] - covered,
+    );
+    let lose = stacks_after(
+        hand_start[hero] - covered,
+        hand_start[caller] + covered + dead_money,
+    );
+
+    let chip_ev = win_probability * win[hero] as f64 + (1.0 - win_probability) * lose[hero] as f64
+        - fold[hero] as f64;
+    let dollar_ev = win_probability * icm_equities(&win, payouts)[hero]
+        + (1.0 - win_probability) * icm_equities(&lose, payouts)[hero]
+        - icm_equities(&fold, payouts)[hero];
+
+    EvDelta { chip_ev, dollar_ev }
+}
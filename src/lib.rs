@@ -0,0 +1,38 @@
+pub mod action;
+pub mod betting;
+pub mod components;
+pub mod conservation;
+pub mod decision;
+pub mod display;
+pub mod equity;
+pub mod error;
+pub mod evaluator;
+pub mod hand_history;
+pub mod hand_history_parser;
+pub mod http_api;
+pub mod icm;
+pub mod ndjson;
+pub mod plugin;
+pub mod rake;
+pub mod server;
+pub mod session;
+pub mod snapshot;
+pub mod stats;
+pub mod tournament;
+pub mod tracker;
+pub mod tui;
+pub mod turn;
+pub mod validation;
+pub mod view;
+pub mod watcher;
+
+pub use action::*;
+pub use betting::{BettingRoundClosed, UncalledBetReturned};
+pub use components::*;
+pub use decision::DecisionRequest;
+pub use error::TrackerError;
+pub use hand_history::ActionJournal;
+pub use plugin::{DisplayMode, PokerTrackerPlugin};
+pub use session::{Outcome, TableSession};
+pub use tracker::TrackerState;
+pub use view::{PlayerStatus, PlayerView, Position, Street, TableView};
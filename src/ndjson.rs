@@ -0,0 +1,179 @@
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use bevy::ecs::event::{Events, ManualEventReader};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::action::Action;
+
+/// Bumped on every incompatible change of `ActionRecord` or `Action` serialization.
+pub const ACTION_SCHEMA_VERSION: u32 = 3;
+
+/// One line of an NDJSON action stream. Consecutive lines with the same
+/// `batch` are applied together in one `app.update()`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionRecord {
+    pub version: u32,
+    pub batch: u64,
+    pub action: Action,
+}
+
+#[derive(Debug)]
+pub enum NdjsonError {
+    Io(io::Error),
+    Format { line: usize, err: serde_json::Error },
+    UnsupportedVersion { line: usize, version: u32 },
+}
+
+impl fmt::Display for NdjsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NdjsonError::Io(err) => write!(f, "action stream io error: {err}"),
+            NdjsonError::Format { line, err } => write!(f, "line {line}: {err}"),
+            NdjsonError::UnsupportedVersion { line, version } => write!(
+                f,
+                "line {line}: schema version {version} is not supported, expected {ACTION_SCHEMA_VERSION}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for NdjsonError {}
+
+impl From<io::Error> for NdjsonError {
+    fn from(err: io::Error) -> Self {
+        NdjsonError::Io(err)
+    }
+}
+
+/// Splits an NDJSON stream into action batches. Blank lines are skipped.
+pub struct NdjsonReader<R> {
+    lines: io::Lines<R>,
+    line_num: usize,
+    pending: Option<ActionRecord>,
+}
+
+impl<R: BufRead> NdjsonReader<R> {
+    pub fn new(reader: R) -> Self {
+        NdjsonReader {
+            lines: reader.lines(),
+            line_num: 0,
+            pending: None,
+        }
+    }
+
+    fn next_record(&mut self) -> Option<Result<ActionRecord, NdjsonError>> {
+        for line in self.lines.by_ref() {
+            self.line_num += 1;
+            let line = match line {
+                Ok(line) if line.trim().is_empty() => continue,
+                Ok(line) => line,
+                Err(err) => return Some(Err(err.into())),
+            };
+
+            return Some(
+                serde_json::from_str::<ActionRecord>(&line)
+                    .map_err(|err| NdjsonError::Format {
+                        line: self.line_num,
+                        err,
+                    })
+                    .and_then(|record| {
+                        if record.version == ACTION_SCHEMA_VERSION {
+                            Ok(record)
+                        } else {
+                            Err(NdjsonError::UnsupportedVersion {
+                                line: self.line_num,
+                                version: record.version,
+                            })
+                        }
+                    }),
+            );
+        }
+
+        None
+    }
+}
+
+impl<R: BufRead> Iterator for NdjsonReader<R> {
+    type Item = Result<Vec<Action>, NdjsonError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first_record = match self.pending.take() {
+            Some(record) => record,
+            None => match self.next_record()? {
+                Ok(record) => record,
+                Err(err) => return Some(Err(err)),
+            },
+        };
+
+        let batch = first_record.batch;
+        let mut actions = vec![first_record.action];
+        while let Some(record) = self.next_record() {
+            match record {
+                Ok(record) if record.batch == batch => actions.push(record.action),
+                Ok(record) => {
+                    self.pending = Some(record);
+                    break;
+                }
+                Err(err) => return Some(Err(err)),
+            }
+        }
+
+        Some(Ok(actions))
+    }
+}
+
+pub fn write_record(mut writer: impl Write, record: &ActionRecord) -> io::Result<()> {
+    serde_json::to_writer(&mut writer, record)?;
+    writer.write_all(b"\n")
+}
+
+/// When inserted, `record_actions_system` writes every processed action to it,
+/// one batch per frame.
+#[derive(Resource)]
+pub struct ActionRecorder {
+    writer: Box<dyn Write + Send + Sync>,
+    batch: u64,
+}
+
+impl ActionRecorder {
+    pub fn new(writer: impl Write + Send + Sync + 'static) -> Self {
+        ActionRecorder {
+            writer: Box::new(writer),
+            batch: 0,
+        }
+    }
+}
+
+pub fn record_actions_system(
+    event_source: Res<Events<Action>>,
+    mut event_reader: Local<Option<ManualEventReader<Action>>>,
+    action_recorder: Option<ResMut<ActionRecorder>>,
+) {
+    let event_reader = event_reader.get_or_insert_with(|| event_source.get_reader());
+    let Some(mut action_recorder) = action_recorder else {
+        // Skip actions sent before the recorder was inserted.
+        event_reader.clear(&event_source);
+        return;
+    };
+
+    let action_recorder = &mut *action_recorder;
+    let mut recorded = false;
+    event_reader.iter(&event_source).for_each(|action| {
+        let record = ActionRecord {
+            version: ACTION_SCHEMA_VERSION,
+            batch: action_recorder.batch,
+            action: action.clone(),
+        };
+        if let Err(err) = write_record(&mut action_recorder.writer, &record) {
+            println!("Can't record action {action:?} - {err}");
+        }
+        recorded = true;
+    });
+
+    if recorded {
+        action_recorder.batch += 1;
+        let _ = action_recorder.writer.flush();
+    }
+}
@@ -0,0 +1,131 @@
+use bevy::ecs::event::Events;
+use bevy::prelude::*;
+
+use crate::action::Action;
+use crate::betting::{BettingRoundClosed, UncalledBetReturned};
+use crate::conservation::{check_chip_conservation_system, ChipConservation};
+use crate::decision::{
+    make_decision_system, update_pending_decision_system, DecisionMode, PendingDecision,
+};
+use crate::display::{show_all_players_system, show_board_system, show_game_system};
+use crate::error::TrackerErrors;
+use crate::hand_history::{record_action_journal_system, ActionJournal};
+use crate::ndjson::record_actions_system;
+use crate::stats::{record_table_stats_system, TableStats};
+use crate::tournament::handle_tournament_events;
+use crate::tracker::{handle_parser_events, TrackerState};
+use crate::tui::terminal_ui_system;
+use crate::validation::{
+    read_parser_events_for_validation, validate_board_cards, validate_deck_cards,
+    validate_hole_cards,
+};
+use crate::view::{update_table_view_system, TableView};
+
+/// How the table is shown after each frame. Tracing goes to `bevy::log`,
+/// not to the display.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DisplayMode {
+    Off,
+    /// `println!` dumps of players, game, board and decisions.
+    #[default]
+    Console,
+    /// Full-screen table redrawn in the terminal.
+    Terminal,
+}
+
+/// Registers the `Action` events, the tracker state and its systems.
+/// Parts besides `handle_parser_events` can be switched off.
+#[derive(Debug, Clone)]
+pub struct PokerTrackerPlugin {
+    pub validation: bool,
+    pub display: DisplayMode,
+    pub decisions: bool,
+    pub decision_mode: DecisionMode,
+    /// Debug check of stacks and bets, off by default.
+    pub chip_conservation: bool,
+}
+
+impl Default for PokerTrackerPlugin {
+    fn default() -> Self {
+        PokerTrackerPlugin {
+            validation: true,
+            display: DisplayMode::default(),
+            decisions: true,
+            decision_mode: DecisionMode::default(),
+            chip_conservation: false,
+        }
+    }
+}
+
+impl Plugin for PokerTrackerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Events<Action>>()
+            // .add_event::<Action>()
+            .add_event::<BettingRoundClosed>()
+            .add_event::<UncalledBetReturned>()
+            .init_resource::<TrackerState>()
+            .init_resource::<TrackerErrors>()
+            .init_resource::<TableView>()
+            .init_resource::<ActionJournal>()
+            .init_resource::<TableStats>()
+            .init_resource::<PendingDecision>()
+            .insert_resource(self.display)
+            .add_system(handle_parser_events)
+            .add_system(handle_tournament_events.after(handle_parser_events))
+            .add_system(record_action_journal_system.after(handle_parser_events))
+            .add_system(record_table_stats_system)
+            .add_system(record_actions_system)
+            .add_system(update_table_view_system.in_base_set(CoreSet::PostUpdate))
+            .add_system(
+                update_pending_decision_system
+                    .in_base_set(CoreSet::PostUpdate)
+                    .after(update_table_view_system),
+            );
+
+        if self.validation {
+            app.add_system(
+                read_parser_events_for_validation
+                    .pipe(validate_board_cards)
+                    .pipe(validate_hole_cards)
+                    .pipe(validate_deck_cards)
+                    .pipe(ignore)
+                    .in_base_set(CoreSet::PreUpdate),
+            );
+        }
+
+        if self.chip_conservation {
+            app.init_resource::<ChipConservation>().add_system(
+                check_chip_conservation_system
+                    .in_base_set(CoreSet::PostUpdate)
+                    .before(update_table_view_system),
+            );
+        }
+
+        match self.display {
+            DisplayMode::Off => (),
+            DisplayMode::Console => {
+                app.add_systems(
+                    (
+                        show_all_players_system.in_base_set(CoreSet::PostUpdateFlush),
+                        show_game_system.in_base_set(CoreSet::PostUpdateFlush),
+                        show_board_system.in_base_set(CoreSet::PostUpdateFlush),
+                    )
+                        .chain()
+                        .before(make_decision_system),
+                );
+            }
+            DisplayMode::Terminal => {
+                app.add_system(
+                    terminal_ui_system
+                        .in_base_set(CoreSet::PostUpdateFlush)
+                        .before(make_decision_system),
+                );
+            }
+        }
+
+        if self.decisions {
+            app.insert_resource(self.decision_mode)
+                .add_system(make_decision_system.in_base_set(CoreSet::PostUpdateFlush));
+        }
+    }
+}
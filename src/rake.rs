@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// How the house rakes the pots of a cash game table, amounts as in `GameLimit`.
+#[derive(Component, Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RakeRules {
+    /// Share of the pot in basis points, 500 for 5%.
+    pub rate_bps: u32,
+    /// Most rake taken from one pot, by players dealt in. Unlimited when none applies.
+    #[serde(default)]
+    pub caps: Vec<RakeCap>,
+    /// Hands that end before the flop are not raked.
+    #[serde(default)]
+    pub no_flop_no_drop: bool,
+    /// Taken on top of the rake, 0 without a jackpot.
+    #[serde(default)]
+    pub jackpot_fee: u64,
+    /// Smallest pot the jackpot fee is taken from.
+    #[serde(default)]
+    pub jackpot_min_pot: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RakeCap {
+    /// The cap holds from this many players dealt in up to the next cap.
+    pub min_players: u8,
+    pub cap: u64,
+}
+
+impl RakeRules {
+    /// Rake and jackpot fee of `pot`, `players` dealt in the hand.
+    pub fn rake(&self, pot: u64, players: usize, flop_dealt: bool) -> HandRake {
+        if self.no_flop_no_drop && !flop_dealt {
+            return HandRake { pot, ..default() };
+        }
+
+        let cap = self
+            .caps
+            .iter()
+            .filter(|cap| usize::from(cap.min_players) <= players)
+            .max_by_key(|cap| cap.min_players)
+            .map_or(u64::MAX, |cap| cap.cap);
+        // A rate over 100% still can't take more than the pot
+        let rake = (pot * u64::from(self.rate_bps) / 10_000).min(cap).min(pot);
+        let jackpot_fee = if pot >= self.jackpot_min_pot {
+            self.jackpot_fee.min(pot - rake)
+        } else {
+            0
+        };
+
+        HandRake {
+            pot,
+            rake,
+            jackpot_fee,
+        }
+    }
+}
+
+/// What the house took from the pot of the current hand, set when the pot is awarded.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HandRake {
+    pub pot: u64,
+    pub rake: u64,
+    pub jackpot_fee: u64,
+}
+
+impl HandRake {
+    /// Rake and jackpot fee together, `Rake` in the hand history summary.
+    pub fn total(&self) -> u64 {
+        self.rake + self.jackpot_fee
+    }
+
+    /// Rake of a pot the winners collected `awarded` from: the one the table rules
+    /// ask for when it agrees with the amounts, otherwise whatever the winners left.
+    pub fn of_award(pot: u64, awarded: u64, rules_rake: Option<HandRake>) -> HandRake {
+        let taken = pot.saturating_sub(awarded);
+        rules_rake
+            .filter(|rules_rake| rules_rake.total() == taken)
+            .unwrap_or(HandRake {
+                pot,
+                rake: taken,
+                jackpot_fee: 0,
+            })
+    }
+
+    /// Splits what is left of the pot between `winners`, odd chips go to the first ones.
+    /// For callers who know the winners but not the amounts of `PotAwarded`.
+    /// Nothing is left when the house took the whole pot or more.
+    pub fn shares(&self, winners: usize) -> Vec<u64> {
+        let awarded = self.pot.saturating_sub(self.total());
+        let winners = winners as u64;
+
+        (0..winners)
+            .map(|index| awarded / winners + u64::from(index < awarded % winners))
+            .collect()
+    }
+}
@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+
+use crate::action::Action;
+use crate::decision::DecisionRequest;
+use crate::error::TrackerError;
+use crate::http_api::{handle_http_connection, QueryReply};
+use crate::session::TableSession;
+
+/// One line sent by a capture process: a batch of actions for one table.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerRequest {
+    pub table: String,
+    pub actions: Vec<Action>,
+}
+
+/// One line sent back for every request line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ServerReply {
+    Ok {
+        table: String,
+        decision: Option<DecisionRequest>,
+        /// Applied anyway, see `TrackerError::is_warning`.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        warnings: Vec<TrackerError>,
+    },
+    Rejected {
+        table: String,
+        errors: Vec<TrackerError>,
+    },
+    BadRequest {
+        message: String,
+    },
+}
+
+/// Tables tracked by the server, created on their first request.
+#[derive(Default)]
+pub struct TrackerServer {
+    pub(crate) sessions: HashMap<String, TableSession>,
+}
+
+impl TrackerServer {
+    pub fn handle(&mut self, request: ServerRequest) -> ServerReply {
+        let table_session = self.sessions.entry(request.table.clone()).or_default();
+
+        match table_session.apply(request.actions) {
+            Ok(outcome) => ServerReply::Ok {
+                table: request.table,
+                decision: outcome.decision,
+                warnings: outcome.warnings,
+            },
+            Err(tracker_errors) => ServerReply::Rejected {
+                table: request.table,
+                errors: tracker_errors.0,
+            },
+        }
+    }
+
+    fn handle_line(&mut self, line: &str) -> ServerReply {
+        match serde_json::from_str::<ServerRequest>(line) {
+            Ok(request) => self.handle(request),
+            Err(err) => ServerReply::BadRequest {
+                message: err.to_string(),
+            },
+        }
+    }
+}
+
+/// Work for the thread owning the sessions, sent by connection threads.
+pub(crate) enum ServerCommand {
+    Ingest(String, Sender<ServerReply>),
+    Query(String, Sender<QueryReply>),
+}
+
+/// Accepts newline-delimited JSON `ServerRequest`s on `addr` until the process stops,
+/// and when `http_addr` is set, serves the read-only query API there as well.
+/// Connections are read on their own threads, all tables are updated on the calling one.
+pub fn serve(addr: impl ToSocketAddrs, http_addr: Option<impl ToSocketAddrs>) -> io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    let http_listener = http_addr.map(TcpListener::bind).transpose()?;
+
+    serve_listeners(listener, http_listener)
+}
+
+/// `serve` on listeners bound by the caller, e.g. to an ephemeral port.
+pub fn serve_listeners(
+    listener: TcpListener,
+    http_listener: Option<TcpListener>,
+) -> io::Result<()> {
+    println!("Tracker server listens on {}", listener.local_addr()?);
+
+    let (command_sender, command_receiver) = mpsc::channel::<ServerCommand>();
+
+    if let Some(http_listener) = http_listener {
+        println!(
+            "Tracker query API listens on {}",
+            http_listener.local_addr()?
+        );
+        let command_sender = command_sender.clone();
+        thread::spawn(move || {
+            accept_connections(http_listener, command_sender, handle_http_connection)
+        });
+    }
+
+    thread::spawn(move || accept_connections(listener, command_sender, handle_connection));
+
+    run_sessions(command_receiver);
+
+    Ok(())
+}
+
+fn accept_connections(
+    listener: TcpListener,
+    command_sender: Sender<ServerCommand>,
+    handle: fn(TcpStream, Sender<ServerCommand>) -> io::Result<()>,
+) {
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                let command_sender = command_sender.clone();
+                thread::spawn(move || {
+                    if let Err(err) = handle(stream, command_sender) {
+                        println!("Tracker server connection closed - {err}");
+                    }
+                });
+            }
+            Err(err) => println!("Tracker server can't accept connection - {err}"),
+        }
+    }
+}
+
+fn run_sessions(command_receiver: Receiver<ServerCommand>) {
+    let mut tracker_server = TrackerServer::default();
+    for command in command_receiver {
+        match command {
+            ServerCommand::Ingest(line, reply_sender) => {
+                let _ = reply_sender.send(tracker_server.handle_line(&line));
+            }
+            ServerCommand::Query(path, reply_sender) => {
+                let _ = reply_sender.send(tracker_server.query(&path));
+            }
+        }
+    }
+}
+
+fn handle_connection(stream: TcpStream, command_sender: Sender<ServerCommand>) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let (reply_sender, reply_receiver) = mpsc::channel();
+
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        command_sender
+            .send(ServerCommand::Ingest(line, reply_sender.clone()))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "tracker stopped"))?;
+        let reply = reply_receiver
+            .recv()
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "tracker stopped"))?;
+        serde_json::to_writer(&mut writer, &reply)?;
+        writer.write_all(b"\n")?;
+    }
+
+    Ok(())
+}
+
+/// Test client standing in for the capture process.
+pub struct TrackerClient {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl TrackerClient {
+    pub fn connect(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let writer = TcpStream::connect(addr)?;
+        let reader = BufReader::new(writer.try_clone()?);
+
+        Ok(TrackerClient { reader, writer })
+    }
+
+    pub fn send(&mut self, table: &str, actions: Vec<Action>) -> io::Result<ServerReply> {
+        let request = ServerRequest {
+            table: table.to_string(),
+            actions,
+        };
+        serde_json::to_writer(&mut self.writer, &request)?;
+        self.writer.write_all(b"\n")?;
+
+        let mut reply_line = String::new();
+        self.reader.read_line(&mut reply_line)?;
+        serde_json::from_str(&reply_line).map_err(io::Error::from)
+    }
+}
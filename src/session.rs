@@ -0,0 +1,250 @@
+use std::any::Any;
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+
+use bevy::ecs::event::{Events, ManualEventReader};
+use bevy::prelude::*;
+
+use crate::action::Action;
+use crate::betting::{BettingRoundClosed, UncalledBetReturned};
+use crate::decision::{DecisionRequest, PendingDecision};
+use crate::error::{TrackerError, TrackerErrors};
+use crate::hand_history::ActionJournal;
+use crate::plugin::{DisplayMode, PokerTrackerPlugin};
+use crate::snapshot::{self, SnapshotError};
+use crate::stats::TableStats;
+use crate::view::TableView;
+
+/// Result of one successfully applied batch of actions.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Outcome {
+    pub processed: usize,
+    pub decision: Option<DecisionRequest>,
+    /// Streets the batch closed, in order.
+    pub rounds_closed: Vec<BettingRoundClosed>,
+    /// Unmatched bets the batch gave back.
+    pub uncalled_bets: Vec<UncalledBetReturned>,
+    /// Reported for actions that were applied anyway, see `TrackerError::is_warning`.
+    pub warnings: Vec<TrackerError>,
+}
+
+/// One tracked table. Owns the Bevy `App` and drives it batch by batch.
+pub struct TableSession {
+    app: App,
+    plugin: PokerTrackerPlugin,
+    /// Set once a batch panicked, a Bevy `App` is not usable after that.
+    failure: Option<String>,
+    rounds_closed_reader: ManualEventReader<BettingRoundClosed>,
+    uncalled_bets_reader: ManualEventReader<UncalledBetReturned>,
+}
+
+/// Tracker plugin of `TableSession::new`.
+fn quiet_plugin() -> PokerTrackerPlugin {
+    PokerTrackerPlugin {
+        display: DisplayMode::Off,
+        ..default()
+    }
+}
+
+impl Default for TableSession {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TableSession {
+    /// Session without console output.
+    pub fn new() -> Self {
+        Self::with_plugin(quiet_plugin())
+    }
+
+    pub fn with_plugin(plugin: PokerTrackerPlugin) -> Self {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins).add_plugin(plugin.clone());
+
+        TableSession {
+            app,
+            plugin,
+            failure: None,
+            rounds_closed_reader: default(),
+            uncalled_bets_reader: default(),
+        }
+    }
+
+    pub fn from_snapshot(path: impl AsRef<Path>) -> Result<Self, SnapshotError> {
+        Self::from_snapshot_with_plugin(quiet_plugin(), path)
+    }
+
+    /// Restores the snapshot into a session built with `plugin`.
+    pub fn from_snapshot_with_plugin(
+        plugin: PokerTrackerPlugin,
+        path: impl AsRef<Path>,
+    ) -> Result<Self, SnapshotError> {
+        let mut table_session = Self::with_plugin(plugin);
+        snapshot::restore_from_file(&mut table_session.app.world, path)?;
+        table_session.app.update();
+
+        Ok(table_session)
+    }
+
+    pub fn save_snapshot(&mut self, path: impl AsRef<Path>) -> Result<(), SnapshotError> {
+        snapshot::save_to_file(&mut self.app.world, path)
+    }
+
+    /// Sends the batch as one frame. When an action is rejected every reported error
+    /// is returned in order, the state changes made by the rest of the batch are kept.
+    /// Errors come as `TrackerErrors` rather than one `TrackerError`,
+    /// a batch can reject several actions. Warnings alone end up in `Outcome::warnings`.
+    /// A panic while handling the batch fails the session for good.
+    pub fn apply(
+        &mut self,
+        actions: impl IntoIterator<Item = Action>,
+    ) -> Result<Outcome, TrackerErrors> {
+        let actions = actions.into_iter().collect::<Vec<_>>();
+        let journal = self
+            .plugin
+            .chip_conservation
+            .then(|| self.journal().actions().to_vec());
+
+        let mut result = self.apply_batch(actions.clone());
+        if let Some(journal) = journal {
+            let tracker_errors = match &mut result {
+                Ok(outcome) => &mut outcome.warnings,
+                Err(tracker_errors) => &mut tracker_errors.0,
+            };
+            self.locate_chips_not_conserved(&journal, &actions, tracker_errors);
+        }
+
+        result
+    }
+
+    fn apply_batch(&mut self, actions: Vec<Action>) -> Result<Outcome, TrackerErrors> {
+        if let Some(failure) = &self.failure {
+            return Err(TrackerErrors(vec![TrackerError::SessionFailed(
+                failure.clone(),
+            )]));
+        }
+
+        let processed = actions.len();
+        let mut event_source = self.app.world.resource_mut::<Events<Action>>();
+        actions
+            .into_iter()
+            .for_each(|action| event_source.send(action));
+        if let Err(panic_payload) = panic::catch_unwind(AssertUnwindSafe(|| self.app.update())) {
+            let failure = panic_message(panic_payload);
+            self.failure = Some(failure.clone());
+            return Err(TrackerErrors(vec![TrackerError::SessionFailed(failure)]));
+        }
+
+        let rounds_closed = self
+            .rounds_closed_reader
+            .iter(self.app.world.resource::<Events<BettingRoundClosed>>())
+            .cloned()
+            .collect();
+        let uncalled_bets = self
+            .uncalled_bets_reader
+            .iter(self.app.world.resource::<Events<UncalledBetReturned>>())
+            .cloned()
+            .collect();
+
+        let tracker_errors = mem::take(&mut *self.app.world.resource_mut::<TrackerErrors>());
+        if !tracker_errors.0.iter().all(TrackerError::is_warning) {
+            return Err(tracker_errors);
+        }
+
+        Ok(Outcome {
+            processed,
+            decision: self.pending_decision(),
+            rounds_closed,
+            uncalled_bets,
+            warnings: tracker_errors.0,
+        })
+    }
+
+    /// The conservation check only sees the chips once the whole batch was handled.
+    /// Replays the journal and then the batch action by action on a copy of the table,
+    /// so the report names the first action that broke it.
+    fn locate_chips_not_conserved(
+        &self,
+        journal: &[Action],
+        actions: &[Action],
+        tracker_errors: &mut [TrackerError],
+    ) {
+        let Some(chips_not_conserved) = tracker_errors
+            .iter_mut()
+            .find(|err| matches!(err, TrackerError::ChipsNotConserved { .. }))
+        else {
+            return;
+        };
+
+        let mut replay_session = TableSession::with_plugin(PokerTrackerPlugin {
+            display: DisplayMode::Off,
+            decisions: false,
+            ..self.plugin.clone()
+        });
+        journal.iter().for_each(|action| {
+            let _ = replay_session.apply_batch(vec![action.clone()]);
+        });
+        for (index, action) in actions.iter().enumerate() {
+            let replay_errors = match replay_session.apply_batch(vec![action.clone()]) {
+                Ok(outcome) => outcome.warnings,
+                Err(tracker_errors) => tracker_errors.0,
+            };
+            if let Some(TrackerError::ChipsNotConserved {
+                expected, actual, ..
+            }) = replay_errors
+                .into_iter()
+                .find(|err| matches!(err, TrackerError::ChipsNotConserved { .. }))
+            {
+                *chips_not_conserved = TrackerError::ChipsNotConserved {
+                    index,
+                    action: action.clone(),
+                    expected,
+                    actual,
+                };
+                return;
+            }
+        }
+    }
+
+    /// For input plugins such as `HandHistoryFilePlugin`, added before the first batch.
+    pub(crate) fn add_plugin(&mut self, plugin: impl Plugin) {
+        self.app.add_plugin(plugin);
+    }
+
+    pub(crate) fn resource<R: Resource>(&self) -> Option<&R> {
+        self.app.world.get_resource::<R>()
+    }
+
+    pub fn table_view(&self) -> TableView {
+        self.app.world.resource::<TableView>().clone()
+    }
+
+    pub fn pending_decision(&self) -> Option<DecisionRequest> {
+        self.app.world.resource::<PendingDecision>().0.clone()
+    }
+
+    /// Every action applied in this session.
+    pub fn journal(&self) -> &ActionJournal {
+        self.app.world.resource::<ActionJournal>()
+    }
+
+    /// Stats of every hand of the session, the journal keeps only the last ones.
+    pub fn stats(&self) -> &TableStats {
+        self.app.world.resource::<TableStats>()
+    }
+
+    /// Last hand of the session as PokerStars hand history text.
+    pub fn hand_history(&self) -> Option<String> {
+        self.journal().last_hand_history()
+    }
+}
+
+fn panic_message(panic_payload: Box<dyn Any + Send>) -> String {
+    panic_payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| panic_payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
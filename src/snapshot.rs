@@ -0,0 +1,451 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::action::Card;
+use crate::betting::{BetRange, BettingRound};
+use crate::components::*;
+use crate::conservation::ChipConservation;
+use crate::hand_history::ActionJournal;
+use crate::rake::{HandRake, RakeRules};
+use crate::stats::TableStats;
+use crate::tracker::TrackerState;
+
+/// Bumped on every incompatible change of the snapshot layout.
+pub const SNAPSHOT_VERSION: u32 = 5;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorldSnapshot {
+    version: u32,
+    game: Option<GameSnapshot>,
+    board: Option<BoardSnapshot>,
+    round_max_bet: Option<u64>,
+    players: Vec<PlayerSnapshot>,
+    betting_round: BettingRound,
+    #[serde(default)]
+    blind_seats: Option<BlindSeats>,
+    tournament: Option<TournamentSnapshot>,
+    /// Kept hands, so the hand in progress can still be exported after a restore.
+    journal: ActionJournal,
+    /// Counts the trimmed hands as well.
+    stats: TableStats,
+    /// Only when the chip conservation check is on.
+    chip_conservation: Option<ChipConservation>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct GameSnapshot {
+    hand_id: Option<String>,
+    game_type: Option<GameType>,
+    game_variant: Option<GameVariant>,
+    game_limit: Option<GameLimit>,
+    max_seats: Option<u8>,
+    #[serde(default)]
+    table_name: Option<String>,
+    dealer_seat_num: Option<u8>,
+    rake_rules: Option<RakeRules>,
+    rake: Option<HandRake>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TournamentSnapshot {
+    tournament_id: String,
+    schedule: BlindSchedule,
+    level: TournamentLevel,
+    hands: Vec<String>,
+    eliminations: Vec<Elimination>,
+    #[serde(default)]
+    payouts: Vec<u64>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BoardSnapshot {
+    cards: Option<Vec<Card>>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PlayerSnapshot {
+    name: String,
+    seat_num: u8,
+    npc: bool,
+    dealer: bool,
+    stack: Option<u64>,
+    cards: Option<Vec<Card>>,
+    round_betting: Option<bool>,
+    round_bets: Option<Vec<u64>>,
+    #[serde(default)]
+    ante: u64,
+    need_decision: Option<bool>,
+    bet_range: Option<BetRange>,
+    bounty: Option<u64>,
+    eliminated_place: Option<u32>,
+    #[serde(default)]
+    sitting_out: bool,
+    /// Left the table, kept only until the hand is over.
+    #[serde(default)]
+    leaving: bool,
+}
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(io::Error),
+    Format(serde_json::Error),
+    UnsupportedVersion(u32),
+    NotEmpty,
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Io(err) => write!(f, "snapshot io error: {err}"),
+            SnapshotError::Format(err) => write!(f, "snapshot format error: {err}"),
+            SnapshotError::UnsupportedVersion(version) => write!(
+                f,
+                "snapshot version {version} is not supported, expected {SNAPSHOT_VERSION}"
+            ),
+            SnapshotError::NotEmpty => write!(f, "snapshot can be restored only into a fresh App"),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<io::Error> for SnapshotError {
+    fn from(err: io::Error) -> Self {
+        SnapshotError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for SnapshotError {
+    fn from(err: serde_json::Error) -> Self {
+        SnapshotError::Format(err)
+    }
+}
+
+impl WorldSnapshot {
+    /// Collects Game, Board, Player entities, `TrackerState`, the action journal,
+    /// table stats and the chip conservation check from the world.
+    pub fn capture(world: &mut World) -> Self {
+        let game = world
+            .query_filtered::<(
+                Option<&GameHandId>,
+                Option<&GameType>,
+                Option<&GameVariant>,
+                Option<&GameLimit>,
+                Option<&GameMaxSeats>,
+                Option<&GameTableName>,
+                Option<&DealerSeatNum>,
+                Option<&RakeRules>,
+                Option<&HandRake>,
+            ), With<Game>>()
+            .iter(world)
+            .next()
+            .map(
+                |(
+                    hand_id,
+                    game_type,
+                    game_variant,
+                    game_limit,
+                    max_seats,
+                    table_name,
+                    dealer_seat_num,
+                    rake_rules,
+                    rake,
+                )| GameSnapshot {
+                    hand_id: hand_id.map(|hand_id| hand_id.0.clone()),
+                    game_type: game_type.copied(),
+                    game_variant: game_variant.copied(),
+                    game_limit: game_limit.copied(),
+                    max_seats: max_seats.map(|max_seats| max_seats.0),
+                    table_name: table_name.map(|table_name| table_name.0.clone()),
+                    dealer_seat_num: dealer_seat_num.map(|dealer_seat_num| dealer_seat_num.0),
+                    rake_rules: rake_rules.cloned(),
+                    rake: rake.copied(),
+                },
+            );
+
+        let board = world
+            .query_filtered::<Option<&BoardCards>, With<Board>>()
+            .iter(world)
+            .next()
+            .map(|board_cards| BoardSnapshot {
+                cards: board_cards.map(|board_cards| board_cards.0.clone()),
+            });
+
+        let round_max_bet = world
+            .query::<&RoundMaxBet>()
+            .iter(world)
+            .next()
+            .map(|round_max_bet| round_max_bet.0);
+
+        let mut players = world
+            .query_filtered::<(
+                &PlayerName,
+                &PlayerSeatNum,
+                Option<&PlayerNpc>,
+                Option<&Dealer>,
+                Option<&PlayerStack>,
+                Option<&PlayerCards>,
+                Option<&PlayerRoundBetting>,
+                Option<&PlayerRoundBets>,
+                Option<&PlayerAnte>,
+                Option<&NeedDecision>,
+                Option<&PlayerBetRange>,
+                Option<&PlayerBounty>,
+                Option<&PlayerEliminated>,
+                Option<&PlayerSittingOut>,
+                Option<&PlayerLeaving>,
+            ), With<Player>>()
+            .iter(world)
+            .map(
+                |(
+                    name,
+                    seat_num,
+                    npc,
+                    dealer,
+                    stack,
+                    cards,
+                    round_betting,
+                    round_bets,
+                    ante,
+                    need_decision,
+                    bet_range,
+                    bounty,
+                    eliminated,
+                    sitting_out,
+                    leaving,
+                )| PlayerSnapshot {
+                    name: name.0.clone(),
+                    seat_num: seat_num.0,
+                    npc: npc.is_some(),
+                    dealer: dealer.is_some(),
+                    stack: stack.map(|stack| stack.0),
+                    cards: cards.map(|cards| cards.0.clone()),
+                    round_betting: round_betting.map(|round_betting| round_betting.0),
+                    round_bets: round_bets.map(|round_bets| round_bets.0.clone()),
+                    ante: ante.map_or(0, |ante| ante.0),
+                    need_decision: need_decision.map(|need_decision| need_decision.0),
+                    bet_range: bet_range.map(|bet_range| bet_range.0),
+                    bounty: bounty.map(|bounty| bounty.0),
+                    eliminated_place: eliminated.map(|eliminated| eliminated.place),
+                    sitting_out: sitting_out.is_some(),
+                    leaving: leaving.is_some(),
+                },
+            )
+            .collect::<Vec<_>>();
+        players.sort_by_key(|player| player.seat_num);
+
+        let tournament = world
+            .query_filtered::<(
+                &TournamentId,
+                &BlindSchedule,
+                &TournamentLevel,
+                &TournamentHands,
+                &TournamentEliminations,
+                &TournamentPayouts,
+            ), With<Tournament>>()
+            .iter(world)
+            .next()
+            .map(
+                |(tournament_id, schedule, level, hands, eliminations, payouts)| {
+                    TournamentSnapshot {
+                        tournament_id: tournament_id.0.clone(),
+                        schedule: schedule.clone(),
+                        level: *level,
+                        hands: hands.0.clone(),
+                        eliminations: eliminations.0.clone(),
+                        payouts: payouts.0.clone(),
+                    }
+                },
+            );
+
+        let betting_round = world
+            .get_resource::<TrackerState>()
+            .map(|tracker_state| tracker_state.betting_round.clone())
+            .unwrap_or_default();
+        let blind_seats = world
+            .get_resource::<TrackerState>()
+            .and_then(|tracker_state| tracker_state.blind_seats);
+        let journal = world
+            .get_resource::<ActionJournal>()
+            .cloned()
+            .unwrap_or_default();
+        let stats = world
+            .get_resource::<TableStats>()
+            .cloned()
+            .unwrap_or_default();
+        let chip_conservation = world.get_resource::<ChipConservation>().cloned();
+
+        WorldSnapshot {
+            version: SNAPSHOT_VERSION,
+            game,
+            board,
+            round_max_bet,
+            players,
+            betting_round,
+            blind_seats,
+            tournament,
+            journal,
+            stats,
+            chip_conservation,
+        }
+    }
+
+    /// Spawns the snapshotted entities and rebuilds `TrackerState` around them.
+    /// The world must not contain tracker entities yet.
+    pub fn restore(self, world: &mut World) -> Result<(), SnapshotError> {
+        if self.version != SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(self.version));
+        }
+
+        let is_fresh = match world.get_resource::<TrackerState>() {
+            Some(tracker_state) => tracker_state.is_empty(),
+            None => true,
+        };
+        if !is_fresh {
+            return Err(SnapshotError::NotEmpty);
+        }
+
+        let mut tracker_state = TrackerState {
+            betting_round: self.betting_round,
+            blind_seats: self.blind_seats,
+            ..default()
+        };
+
+        if let Some(game) = self.game {
+            let mut game_entity = world.spawn(Game);
+            if let Some(hand_id) = game.hand_id {
+                game_entity.insert(GameHandId(hand_id));
+            }
+            if let Some(game_type) = game.game_type {
+                game_entity.insert(game_type);
+            }
+            if let Some(game_variant) = game.game_variant {
+                game_entity.insert(game_variant);
+            }
+            if let Some(game_limit) = game.game_limit {
+                game_entity.insert(game_limit);
+            }
+            if let Some(max_seats) = game.max_seats {
+                game_entity.insert(GameMaxSeats(max_seats));
+            }
+            if let Some(table_name) = game.table_name {
+                game_entity.insert(GameTableName(table_name));
+            }
+            if let Some(dealer_seat_num) = game.dealer_seat_num {
+                game_entity.insert(DealerSeatNum(dealer_seat_num));
+            }
+            if let Some(rake_rules) = game.rake_rules {
+                game_entity.insert(rake_rules);
+            }
+            if let Some(rake) = game.rake {
+                game_entity.insert(rake);
+            }
+            tracker_state.game_entity = Some(game_entity.id());
+        }
+
+        if let Some(tournament) = self.tournament {
+            let tournament_entity = world.spawn((
+                Tournament,
+                TournamentId(tournament.tournament_id),
+                tournament.schedule,
+                tournament.level,
+                TournamentHands(tournament.hands),
+                TournamentEliminations(tournament.eliminations),
+                TournamentPayouts(tournament.payouts),
+            ));
+            tracker_state.tournament_entity = Some(tournament_entity.id());
+        }
+
+        if let Some(board) = self.board {
+            let mut board_entity = world.spawn(Board);
+            if let Some(cards) = board.cards {
+                board_entity.insert(BoardCards(cards));
+            }
+            tracker_state.board_entity = Some(board_entity.id());
+        }
+
+        if let Some(round_max_bet) = self.round_max_bet {
+            world.spawn(RoundMaxBet(round_max_bet));
+        }
+
+        for player in self.players {
+            let mut player_entity = world.spawn((
+                Player,
+                PlayerName(player.name.clone()),
+                PlayerSeatNum(player.seat_num),
+                PlayerAnte(player.ante),
+            ));
+            if player.npc {
+                player_entity.insert(PlayerNpc);
+            }
+            if player.dealer {
+                player_entity.insert(Dealer);
+            }
+            if let Some(stack) = player.stack {
+                player_entity.insert(PlayerStack(stack));
+            }
+            if let Some(cards) = player.cards {
+                player_entity.insert(PlayerCards(cards));
+            }
+            if let Some(round_betting) = player.round_betting {
+                player_entity.insert(PlayerRoundBetting(round_betting));
+            }
+            if let Some(round_bets) = player.round_bets {
+                player_entity.insert(PlayerRoundBets(round_bets));
+            }
+            if let Some(need_decision) = player.need_decision {
+                player_entity.insert(NeedDecision(need_decision));
+            }
+            if let Some(bet_range) = player.bet_range {
+                player_entity.insert(PlayerBetRange(bet_range));
+            }
+            if let Some(bounty) = player.bounty {
+                player_entity.insert(PlayerBounty(bounty));
+            }
+            if let Some(place) = player.eliminated_place {
+                player_entity.insert(PlayerEliminated { place });
+            }
+            if player.sitting_out {
+                player_entity.insert(PlayerSittingOut);
+            }
+            if player.leaving {
+                player_entity.insert(PlayerLeaving);
+                continue;
+            }
+
+            let player_entity = player_entity.id();
+            tracker_state
+                .players_hmap
+                .insert(player.name, player_entity);
+            tracker_state
+                .players_hmap
+                .insert(player.seat_num.to_string(), player_entity);
+        }
+
+        world.insert_resource(tracker_state);
+        world.insert_resource(self.journal);
+        world.insert_resource(self.stats);
+        if let Some(chip_conservation) = self.chip_conservation {
+            world.insert_resource(chip_conservation);
+        }
+
+        Ok(())
+    }
+}
+
+pub fn save_to_file(world: &mut World, path: impl AsRef<Path>) -> Result<(), SnapshotError> {
+    let snapshot = WorldSnapshot::capture(world);
+    fs::write(path, serde_json::to_string_pretty(&snapshot)?)?;
+
+    Ok(())
+}
+
+pub fn restore_from_file(world: &mut World, path: impl AsRef<Path>) -> Result<(), SnapshotError> {
+    let snapshot: WorldSnapshot = serde_json::from_str(&fs::read_to_string(path)?)?;
+    snapshot.restore(world)
+}
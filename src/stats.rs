@@ -0,0 +1,203 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::ecs::event::{Events, ManualEventReader};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::action::*;
+use crate::components::GameLimit;
+
+/// Counters of one player collected from the hands of one or more tables.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayerStats {
+    pub name: String,
+    pub hands: u32,
+    /// Hands where the player put money in preflop besides the blinds.
+    pub vpip_hands: u32,
+    pub preflop_raise_hands: u32,
+    pub total_bet: u64,
+    /// `total_bet` of the hands with known stakes, summed up in big blinds.
+    pub total_bet_big_blinds: f64,
+}
+
+impl PlayerStats {
+    pub fn new(name: impl Into<String>) -> Self {
+        PlayerStats {
+            name: name.into(),
+            ..default()
+        }
+    }
+
+    /// Adds the hands of one table journal.
+    pub fn add_journal(&mut self, journal: &[Action]) {
+        let mut table_stats = TableStats::default();
+        journal
+            .iter()
+            .for_each(|action| table_stats.add_action(action));
+        if let Some(player_stats) = table_stats.player_stats(&self.name) {
+            self.add(&player_stats);
+        }
+    }
+
+    /// Adds the counters of the same player from another table.
+    pub fn add(&mut self, other: &PlayerStats) {
+        self.hands += other.hands;
+        self.vpip_hands += other.vpip_hands;
+        self.preflop_raise_hands += other.preflop_raise_hands;
+        self.total_bet += other.total_bet;
+        self.total_bet_big_blinds += other.total_bet_big_blinds;
+    }
+}
+
+/// Stats of every player at one table, counted action by action,
+/// so hands trimmed from the `ActionJournal` are still counted.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TableStats {
+    /// Finished hands only.
+    players: HashMap<String, PlayerStats>,
+    seats: HashMap<u8, String>,
+    game_limit: Option<GameLimit>,
+    hand: Option<HandCounters>,
+}
+
+impl TableStats {
+    pub fn add_action(&mut self, action: &Action) {
+        match action {
+            Action::SeatUpdated(SeatUpdatedParams { name, seat_num, .. }) => {
+                self.seats.insert(*seat_num, name.clone());
+                if let Some(hand) = self.hand.as_mut() {
+                    hand.seated.insert(name.clone());
+                }
+            }
+            Action::GameHandIdSet(_) => {
+                self.finish_hand();
+                self.hand = Some(HandCounters {
+                    seated: self.seats.values().cloned().collect(),
+                    ..default()
+                });
+            }
+            Action::GameLimitSet(game_limit) => self.game_limit = Some(*game_limit),
+            Action::CommunityCardsDealt(_) => {
+                if let Some(hand) = self.hand.as_mut() {
+                    hand.preflop = false;
+                    hand.street_bets.clear();
+                    hand.street_max_bet = 0;
+                }
+            }
+            Action::BetMade(BetMadeParams {
+                seat_index: seat_num,
+                bet_size,
+            }) => {
+                let Some(hand) = self.hand.as_mut() else {
+                    return;
+                };
+                let name = self.seats.get(seat_num);
+                let street_bet = hand.street_bets.entry(*seat_num).or_default();
+                *street_bet += bet_size;
+                let street_bet = *street_bet;
+
+                if hand.preflop && hand.blinds < 2 && *bet_size > 0 {
+                    hand.blinds += 1;
+                } else if let Some(name) = name.filter(|_| hand.preflop && *bet_size > 0) {
+                    hand.vpip.insert(name.clone());
+                    if street_bet > hand.street_max_bet {
+                        hand.preflop_raise.insert(name.clone());
+                    }
+                }
+                if let Some(name) = name {
+                    *hand.total_bets.entry(name.clone()).or_default() += bet_size;
+                }
+                hand.street_max_bet = hand.street_max_bet.max(street_bet);
+            }
+            _ => (),
+        }
+    }
+
+    /// Finished hands and the hand in progress, `None` when the player was never dealt in.
+    pub fn player_stats(&self, name: &str) -> Option<PlayerStats> {
+        let mut player_stats = self
+            .players
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| PlayerStats::new(name));
+        if let Some(hand) = &self.hand {
+            hand.add_to(&mut player_stats, self.game_limit);
+        }
+
+        (player_stats.hands > 0).then_some(player_stats)
+    }
+
+    fn finish_hand(&mut self) {
+        let Some(hand) = self.hand.take() else {
+            return;
+        };
+        for name in &hand.seated {
+            let player_stats = self
+                .players
+                .entry(name.clone())
+                .or_insert_with(|| PlayerStats::new(name.clone()));
+            hand.add_to(player_stats, self.game_limit);
+        }
+    }
+}
+
+/// Counts every sent action, like `record_action_journal_system` records them.
+pub fn record_table_stats_system(
+    event_source: Res<Events<Action>>,
+    mut event_reader: Local<Option<ManualEventReader<Action>>>,
+    mut table_stats: ResMut<TableStats>,
+) {
+    let event_reader = event_reader.get_or_insert_with(|| event_source.get_reader());
+    event_reader
+        .iter(&event_source)
+        .for_each(|action| table_stats.add_action(action));
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct HandCounters {
+    seated: HashSet<String>,
+    preflop: bool,
+    blinds: u8,
+    street_bets: HashMap<u8, u64>,
+    street_max_bet: u64,
+    vpip: HashSet<String>,
+    preflop_raise: HashSet<String>,
+    total_bets: HashMap<String, u64>,
+}
+
+impl HandCounters {
+    fn add_to(&self, player_stats: &mut PlayerStats, game_limit: Option<GameLimit>) {
+        if !self.seated.contains(&player_stats.name) {
+            return;
+        }
+
+        let total_bet = self
+            .total_bets
+            .get(&player_stats.name)
+            .copied()
+            .unwrap_or_default();
+        player_stats.hands += 1;
+        player_stats.vpip_hands += u32::from(self.vpip.contains(&player_stats.name));
+        player_stats.preflop_raise_hands +=
+            u32::from(self.preflop_raise.contains(&player_stats.name));
+        player_stats.total_bet += total_bet;
+        if let Some(game_limit) = game_limit {
+            player_stats.total_bet_big_blinds += game_limit.in_big_blinds(total_bet);
+        }
+    }
+}
+
+impl Default for HandCounters {
+    fn default() -> Self {
+        HandCounters {
+            seated: default(),
+            preflop: true,
+            blinds: 0,
+            street_bets: default(),
+            street_max_bet: 0,
+            vpip: default(),
+            preflop_raise: default(),
+            total_bets: default(),
+        }
+    }
+}
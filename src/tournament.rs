@@ -0,0 +1,136 @@
+use bevy::ecs::event::{Events, ManualEventReader};
+use bevy::prelude::*;
+
+use crate::action::*;
+use crate::components::*;
+use crate::error::{TrackerError, TrackerErrors};
+use crate::tracker::TrackerState;
+
+type TournamentEntities<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static BlindSchedule,
+        &'static mut TournamentLevel,
+        &'static mut TournamentHands,
+        &'static mut TournamentEliminations,
+    ),
+    With<Tournament>,
+>;
+
+/// Runs after `handle_parser_events`: links hands to the `Tournament` entity,
+/// moves through the blind schedule and eliminates players who bust.
+#[allow(clippy::too_many_arguments)]
+pub fn handle_tournament_events(
+    mut commands: Commands,
+    event_source: Res<Events<Action>>,
+    mut event_reader: Local<Option<ManualEventReader<Action>>>,
+    time: Option<Res<Time>>,
+    mut tournament_entities: TournamentEntities,
+    remaining_players: Query<Option<&PlayerBounty>, (With<Player>, Without<PlayerEliminated>)>,
+    mut tracker_state: ResMut<TrackerState>,
+    mut tracker_errors: ResMut<TrackerErrors>,
+) {
+    let actions = event_reader
+        .get_or_insert_with(|| event_source.get_reader())
+        .iter(&event_source)
+        .cloned()
+        .collect::<Vec<_>>();
+    let now = time.map(|time| time.elapsed()).unwrap_or_default();
+    // Stakes from the hand itself win over the schedule
+    let game_limit_set = actions
+        .iter()
+        .any(|action| matches!(action, Action::GameLimitSet(_)));
+    let mut eliminated_now = 0;
+
+    for action in &actions {
+        match action {
+            Action::TournamentStarted(TournamentStartedParams {
+                tournament_id,
+                schedule,
+                payouts,
+            }) => {
+                debug!("Action::TournamentStarted from event source");
+                if let Some(old_tournament_entity) = tracker_state.tournament_entity {
+                    commands.entity(old_tournament_entity).despawn();
+                }
+
+                let tournament_entity = commands
+                    .spawn((
+                        Tournament,
+                        TournamentId(tournament_id.clone()),
+                        schedule.clone(),
+                        TournamentPayouts(payouts.clone()),
+                        TournamentLevel {
+                            started: now,
+                            ..default()
+                        },
+                        TournamentHands::default(),
+                        TournamentEliminations::default(),
+                    ))
+                    .id();
+                tracker_state.tournament_entity = Some(tournament_entity);
+            }
+            Action::GameHandIdSet(hand_id) => {
+                let Some(Ok((schedule, mut level, mut hands, _))) = tracker_state
+                    .tournament_entity
+                    .map(|tournament_entity| tournament_entities.get_mut(tournament_entity))
+                else {
+                    continue;
+                };
+
+                level.start_hand(schedule, now);
+                hands.0.push(hand_id.clone());
+                if let (false, Some(game_entity), Some(blind_level)) = (
+                    game_limit_set,
+                    tracker_state.game_entity,
+                    schedule.level(level.index),
+                ) {
+                    commands
+                        .entity(game_entity)
+                        .insert(blind_level.game_limit());
+                }
+            }
+            Action::BountySet(BountySetParams { name, bounty }) => {
+                debug!("Action::BountySet from event source");
+                let Some(player_entity) = tracker_state.players_hmap.get(name) else {
+                    tracker_errors
+                        .0
+                        .push(TrackerError::UnknownPlayer(name.clone()));
+                    continue;
+                };
+                commands
+                    .entity(*player_entity)
+                    .insert(PlayerBounty(*bounty));
+            }
+            Action::StackUpdated(StackUpdatedParams { name, stack: 0 }) => {
+                let Some(Ok((.., hands, mut eliminations))) = tracker_state
+                    .tournament_entity
+                    .map(|tournament_entity| tournament_entities.get_mut(tournament_entity))
+                else {
+                    continue;
+                };
+                let Some(Ok(bounty)) = tracker_state
+                    .players_hmap
+                    .get(name)
+                    .map(|player_entity| remaining_players.get(*player_entity))
+                else {
+                    continue;
+                };
+
+                let place = (remaining_players.iter().count() - eliminated_now) as u32;
+                eliminated_now += 1;
+                eliminations.0.push(Elimination {
+                    name: name.clone(),
+                    place,
+                    hand_id: hands.0.last().cloned(),
+                    bounty: bounty.map(|bounty| bounty.0),
+                });
+                commands
+                    .entity(tracker_state.players_hmap[name])
+                    .insert(PlayerEliminated { place });
+            }
+            _ => (),
+        }
+    }
+}
@@ -0,0 +1,888 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use bevy::ecs::event::{Events, ManualEventReader};
+use bevy::prelude::*;
+
+use crate::action::*;
+use crate::betting::{BetRange, BettingRound, BettingRoundClosed, SeatState, UncalledBetReturned};
+use crate::components::*;
+use crate::error::{TrackerError, TrackerErrors};
+use crate::rake::{HandRake, RakeRules};
+use crate::view::Street;
+
+/// State of `handle_parser_events` that lives between frames.
+/// Kept as a resource (not `Local`) so it can be snapshotted and restored.
+#[derive(Resource, Default, Debug)]
+pub struct TrackerState {
+    pub(crate) game_entity: Option<Entity>,
+    pub(crate) board_entity: Option<Entity>,
+    pub(crate) players_hmap: HashMap<String, Entity>,
+    pub(crate) betting_round: BettingRound,
+    pub(crate) tournament_entity: Option<Entity>,
+    /// Button and blinds of the current hand, predicted from the previous one.
+    pub(crate) blind_seats: Option<BlindSeats>,
+}
+
+impl TrackerState {
+    /// No action has been handled yet.
+    pub fn is_empty(&self) -> bool {
+        self.game_entity.is_none() && self.players_hmap.is_empty()
+    }
+}
+
+type PlayersEntities<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static PlayerSeatNum,
+        Option<&'static mut PlayerStack>,
+        Option<&'static mut PlayerRoundBets>,
+        Option<&'static PlayerNpc>,
+        Option<&'static mut PlayerRoundBetting>,
+        Option<&'static mut NeedDecision>,
+    ),
+>;
+
+type SittingOutPlayers<'w, 's> =
+    Query<'w, 's, (Entity, Option<&'static PlayerLeaving>), With<PlayerSittingOut>>;
+
+#[allow(clippy::too_many_arguments)]
+pub fn handle_parser_events(
+    mut commands: Commands,
+    event_source: Res<Events<Action>>,
+    mut players_entities: PlayersEntities,
+    player_names: Query<&PlayerName>,
+    mut round_max_bet: Query<(Entity, &mut RoundMaxBet)>,
+    dealer_seat_num_query: Query<&DealerSeatNum>,
+    game_type_query: Query<&GameType>,
+    rake_rules_query: Query<&RakeRules>,
+    mut player_antes: Query<&mut PlayerAnte>,
+    eliminated_query: Query<(), With<PlayerEliminated>>,
+    sitting_out_query: SittingOutPlayers,
+    mut event_reader: Local<Option<ManualEventReader<Action>>>,
+    mut tracker_state: ResMut<TrackerState>,
+    mut tracker_errors: ResMut<TrackerErrors>,
+    mut betting_round_closed: ResMut<Events<BettingRoundClosed>>,
+    mut uncalled_bet_returned: ResMut<Events<UncalledBetReturned>>,
+) {
+    let TrackerState {
+        game_entity,
+        board_entity,
+        players_hmap,
+        betting_round,
+        blind_seats,
+        ..
+    } = &mut *tracker_state;
+    let game_entity = game_entity
+        .get_or_insert_with(|| commands.spawn(Game).id())
+        .to_owned();
+    let board_entity = board_entity
+        .get_or_insert_with(|| commands.spawn(Board).id())
+        .to_owned();
+
+    event_reader
+        .get_or_insert_with(|| event_source.get_reader())
+        .iter(&event_source)
+        .for_each(|event| {
+            match event {
+                // Action::NewGameLaunched => unimplemented!(),
+                Action::GameHandIdSet(hand_id) => {
+                    debug!("Action::GameHandIdSet from event source");
+                    // A new hand on the same table, clean up what is left from the previous one
+                    match round_max_bet.get_single_mut() {
+                        Ok((_, mut round_max_bet)) => round_max_bet.0 = 0,
+                        Err(_) => {
+                            commands.spawn(RoundMaxBet(default()));
+                        }
+                    }
+                    *betting_round = default();
+                    commands.entity(board_entity).remove::<BoardCards>();
+                    player_antes
+                        .iter_mut()
+                        .for_each(|mut player_ante| player_ante.0 = 0);
+
+                    // The button moves on by itself, `GameDealerSeatNumSet` only confirms it
+                    let active_seats =
+                        active_seats(&players_entities, &sitting_out_query, &eliminated_query);
+                    *blind_seats =
+                        blind_seats.and_then(|blind_seats| blind_seats.next_hand(&active_seats));
+                    if let Some(blind_seats) = blind_seats {
+                        betting_round.set_dead_small_blind(blind_seats.dead_small_blind);
+                        commands
+                            .entity(game_entity)
+                            .insert(DealerSeatNum(blind_seats.button));
+                    }
+
+                    players_entities.iter_mut().for_each(
+                        |(
+                            player_entity,
+                            _,
+                            _,
+                            player_round_bets,
+                            _,
+                            player_round_betting,
+                            need_decision,
+                        )| {
+                            if let Some(mut player_round_bets) = player_round_bets {
+                                player_round_bets.0.clear();
+                            }
+                            if let Some(mut player_round_betting) = player_round_betting {
+                                player_round_betting.0 = true;
+                            }
+                            if let Some(mut need_decision) = need_decision {
+                                need_decision.0 = false;
+                            }
+                            commands
+                                .entity(player_entity)
+                                .remove::<(PlayerCards, Dealer)>();
+                        },
+                    );
+                    if let Some(player_entity) = blind_seats.and_then(|blind_seats| {
+                        players_hmap.get(&blind_seats.button.to_string()).copied()
+                    }) {
+                        commands.entity(player_entity).insert(Dealer);
+                    }
+                    sitting_out_query
+                        .iter()
+                        .filter(|(_, player_leaving)| player_leaving.is_some())
+                        .for_each(|(player_entity, _)| commands.entity(player_entity).despawn());
+
+                    commands
+                        .entity(game_entity)
+                        .insert(GameHandId(hand_id.clone()))
+                        .remove::<HandRake>();
+                }
+                Action::GameMaxSeatsSet(max_seats) => {
+                    debug!("Action::GameMaxSeatsSet from event source");
+                    commands
+                        .entity(game_entity)
+                        .insert(GameMaxSeats(*max_seats));
+                }
+                Action::GameTableNameSet(table_name) => {
+                    debug!("Action::GameTableNameSet from event source");
+                    commands
+                        .entity(game_entity)
+                        .insert(GameTableName(table_name.clone()));
+                }
+                Action::GameDealerSeatNumSet(dealer_seat_num) => {
+                    debug!("Action::GameDealerSeatNumSet from event source");
+
+                    let predicted = blind_seats
+                        .filter(|blind_seats| blind_seats.button == *dealer_seat_num)
+                        .is_some();
+                    if !predicted {
+                        if let Some(expected) = blind_seats.map(|blind_seats| blind_seats.button) {
+                            tracker_errors.0.push(TrackerError::UnexpectedButton {
+                                expected,
+                                actual: *dealer_seat_num,
+                            });
+                        }
+                        let active_seats =
+                            active_seats(&players_entities, &sitting_out_query, &eliminated_query);
+                        *blind_seats = BlindSeats::from_button(*dealer_seat_num, &active_seats);
+                        if betting_round.is_blind() {
+                            betting_round.set_dead_small_blind(false);
+                        }
+                    }
+
+                    commands
+                        .entity(game_entity)
+                        .insert(DealerSeatNum(*dealer_seat_num));
+                    players_entities.iter().for_each(|(player_entity, ..)| {
+                        commands.entity(player_entity).remove::<Dealer>();
+                    });
+
+                    let Some((player_entity, ..)) = players_entities
+                        .iter()
+                        .filter(|(_, &PlayerSeatNum(seat_num), ..)| seat_num == *dealer_seat_num)
+                        .min_by_key(|(player_entity, ..)| {
+                            sitting_out_query.contains(*player_entity)
+                        })
+                    else {
+                        // Under the dead button rule nobody may sit on the button
+                        if !predicted {
+                            tracker_errors
+                                .0
+                                .push(TrackerError::UnknownSeat(*dealer_seat_num));
+                        }
+                        return;
+                    };
+                    commands.entity(player_entity).insert(Dealer);
+                }
+                Action::GameTypeSet(game_type) => {
+                    debug!("Action::GameTypeSet from event source");
+                    commands.entity(game_entity).insert(*game_type);
+                }
+                Action::GameVariantSet(game_variant) => {
+                    debug!("Action::GameVariantSet from event source");
+                    commands.entity(game_entity).insert(*game_variant);
+                }
+                Action::GameLimitSet(game_limit) => {
+                    debug!("Action::GameLimitSet from event source");
+                    if game_limit.decimals().is_none() {
+                        tracker_errors.0.push(TrackerError::UnsupportedDenomination(
+                            game_limit.denomination,
+                        ));
+                        return;
+                    }
+                    commands.entity(game_entity).insert(*game_limit);
+                }
+                Action::RakeRulesSet(rake_rules) => {
+                    debug!("Action::RakeRulesSet from event source");
+                    commands.entity(game_entity).insert(rake_rules.clone());
+                }
+                Action::SeatUpdated(seat_params) => {
+                    debug!("Action::SeatUpdated from event source");
+
+                    let mut player_entity_command = commands.spawn((
+                        Player,
+                        PlayerName(seat_params.name.clone()),
+                        PlayerSeatNum(seat_params.seat_num),
+                        PlayerRoundBets(default()),
+                        PlayerAnte(default()),
+                        PlayerRoundBetting(true),
+                    ));
+
+                    if seat_params.npc {
+                        player_entity_command
+                            .insert(PlayerNpc)
+                            .insert(NeedDecision(default()));
+                    }
+
+                    players_hmap.insert(seat_params.name.clone(), player_entity_command.id());
+                    players_hmap
+                        .insert(seat_params.seat_num.to_string(), player_entity_command.id());
+                }
+                Action::StackUpdated(StackUpdatedParams { name, stack }) => {
+                    debug!("Action::StackUpdated from event source");
+
+                    let Some(player_entity) = players_hmap.get(name) else {
+                        tracker_errors
+                            .0
+                            .push(TrackerError::UnknownPlayer(name.clone()));
+                        return;
+                    };
+                    // No rebuys once busted out of a tournament
+                    if *stack > 0 && eliminated_query.contains(*player_entity) {
+                        tracker_errors
+                            .0
+                            .push(TrackerError::PlayerEliminated(name.clone()));
+                        return;
+                    }
+                    commands
+                        .entity(*player_entity)
+                        .insert(PlayerStack(*stack))
+                        .insert(PlayerRoundBetting(true));
+                }
+                Action::NpcCardsDealt(NpcCardsDealtParams { name, cards }) => {
+                    debug!("Action::NpcCardsDealt from event source");
+
+                    let Some(player_entity) = players_hmap.get(name) else {
+                        tracker_errors
+                            .0
+                            .push(TrackerError::UnknownPlayer(name.clone()));
+                        return;
+                    };
+                    commands
+                        .entity(*player_entity)
+                        .insert((PlayerNpc, PlayerCards(cards.clone())));
+                    // Keep the decision asked for before the cards were known. A player
+                    // seated in this batch is not spawned yet and has none.
+                    if !matches!(players_entities.get(*player_entity), Ok((.., Some(_)))) {
+                        commands
+                            .entity(*player_entity)
+                            .insert(NeedDecision(default()));
+                    }
+                }
+                Action::CommunityCardsDealt(CommunityCardsDealtParams {
+                    prev_cards,
+                    new_cards,
+                }) => {
+                    debug!("Action::CommunityCardsDealt from event source");
+
+                    let mut board_cards = prev_cards.clone();
+                    board_cards.extend(new_cards.clone().into_iter());
+                    betting_round.start_street(Street::from_board(&board_cards));
+                    commands
+                        .entity(board_entity)
+                        .insert(BoardCards(board_cards));
+
+                    clear_decisions(&mut players_entities);
+                    // First to act on the street is the first player after the button
+                    let Some(DealerSeatNum(dealer_seat_num)) = dealer_seat_num_query.iter().next()
+                    else {
+                        return;
+                    };
+                    let round_max_bet = round_max_bet
+                        .iter()
+                        .next()
+                        .map_or(0, |(_, round_max_bet)| round_max_bet.0);
+                    let seat_states = seat_states(&players_entities, &sitting_out_query);
+                    let pot = hand_pot(&players_entities, &player_antes);
+                    match betting_round.next_to_act(*dealer_seat_num, &seat_states, round_max_bet) {
+                        Some(next_seat_num) => ask_for_decision(
+                            &mut commands,
+                            &mut players_entities,
+                            next_seat_num,
+                            betting_round.bet_range(
+                                game_type(&game_type_query),
+                                &seat_states,
+                                next_seat_num,
+                                round_max_bet,
+                                pot,
+                            ),
+                        ),
+                        None => betting_round_closed.send(BettingRoundClosed {
+                            street: betting_round.street(),
+                            pot,
+                        }),
+                    }
+                }
+                Action::BetMade(BetMadeParams {
+                    seat_index: bet_seat_num,
+                    bet_size,
+                }) => {
+                    debug!("Action::BetMade from event source");
+
+                    let Ok((_, mut round_max_bet)) = round_max_bet.get_single_mut() else {
+                        tracker_errors.0.push(TrackerError::NoHand);
+                        return;
+                    };
+                    let (bet_player_entity, stack) = match bettor(
+                        &players_entities,
+                        &sitting_out_query,
+                        &player_names,
+                        *bet_seat_num,
+                        *bet_size,
+                    ) {
+                        Ok(bettor) => bettor,
+                        Err(err) => {
+                            tracker_errors.0.push(err);
+                            return;
+                        }
+                    };
+
+                    let prev_round_max_bet = round_max_bet.0;
+                    clear_decisions(&mut players_entities);
+
+                    if let Some(blind_seats) = blind_seats.filter(|_| betting_round.is_blind()) {
+                        let expected = if betting_round.blinds_posted() == 0 {
+                            blind_seats.small_blind
+                        } else {
+                            blind_seats.big_blind
+                        };
+                        if expected != *bet_seat_num {
+                            tracker_errors.0.push(TrackerError::UnexpectedBlind {
+                                seat_num: *bet_seat_num,
+                                expected,
+                            });
+                        }
+                    }
+
+                    if !betting_round.is_blind() {
+                        let seat_states = seat_states(&players_entities, &sitting_out_query);
+                        let bet_range = betting_round.bet_range(
+                            game_type(&game_type_query),
+                            &seat_states,
+                            *bet_seat_num,
+                            round_max_bet.0,
+                            hand_pot(&players_entities, &player_antes),
+                        );
+                        if let Some(bet_range) =
+                            bet_range.filter(|bet_range| !bet_range.allows(*bet_size, stack))
+                        {
+                            tracker_errors.0.push(TrackerError::IllegalBet {
+                                seat_num: *bet_seat_num,
+                                bet_size: *bet_size,
+                                bet_range,
+                            });
+                        }
+                    }
+
+                    players_entities.iter_mut().for_each(
+                        |(
+                            player_entity,
+                            _,
+                            player_stack,
+                            player_round_bets,
+                            _,
+                            _maybe_in_round_betting,
+                            _,
+                        )| {
+                            if player_entity != bet_player_entity {
+                                return;
+                            };
+
+                            let mut player_round_bets = player_round_bets
+                                .expect("PlayerRoundBets should exist for BetMade action");
+                            player_round_bets.0.push(*bet_size);
+
+                            let mut player_stack =
+                                player_stack.expect("PlayerStack should exist for BetMade action");
+                            player_stack.0 -= bet_size;
+                            let player_round_bets_sum = player_round_bets.bets_sum();
+
+                            if player_round_bets_sum > round_max_bet.0 {
+                                round_max_bet.0 = player_round_bets_sum;
+                            }
+
+                            if player_stack.0 == 0
+                                || (*bet_size == 0 && round_max_bet.0 > player_round_bets_sum)
+                            {
+                                _maybe_in_round_betting
+                                    .expect("Player should be in betting")
+                                    .0 = false;
+                            }
+                        },
+                    );
+
+                    let was_closed = betting_round.is_closed();
+                    betting_round.record_bet(
+                        *bet_seat_num,
+                        *bet_size,
+                        round_max_bet.0 - prev_round_max_bet,
+                    );
+                    let seat_states = seat_states(&players_entities, &sitting_out_query);
+                    let pot = hand_pot(&players_entities, &player_antes);
+                    match betting_round.next_to_act(*bet_seat_num, &seat_states, round_max_bet.0) {
+                        Some(next_seat_num) => ask_for_decision(
+                            &mut commands,
+                            &mut players_entities,
+                            next_seat_num,
+                            betting_round.bet_range(
+                                game_type(&game_type_query),
+                                &seat_states,
+                                next_seat_num,
+                                round_max_bet.0,
+                                pot,
+                            ),
+                        ),
+                        None if !was_closed => {
+                            if let Some((player_entity, seat_num, amount)) =
+                                return_uncalled_bet(&mut players_entities, &mut round_max_bet.0)
+                            {
+                                uncalled_bet_returned.send(UncalledBetReturned {
+                                    name: player_names
+                                        .get(player_entity)
+                                        .map(|player_name| player_name.0.clone())
+                                        .unwrap_or_default(),
+                                    seat_num,
+                                    amount,
+                                });
+                            }
+                            betting_round_closed.send(BettingRoundClosed {
+                                street: betting_round.street(),
+                                pot: hand_pot(&players_entities, &player_antes),
+                            });
+                        }
+                        None => (),
+                    }
+                }
+                Action::AntePosted(AntePostedParams {
+                    seat_index: ante_seat_num,
+                    ante,
+                }) => {
+                    debug!("Action::AntePosted from event source");
+
+                    if round_max_bet.is_empty() {
+                        tracker_errors.0.push(TrackerError::NoHand);
+                        return;
+                    }
+                    let ante_player_entity = match bettor(
+                        &players_entities,
+                        &sitting_out_query,
+                        &player_names,
+                        *ante_seat_num,
+                        *ante,
+                    ) {
+                        Ok((player_entity, _)) => player_entity,
+                        Err(err) => {
+                            tracker_errors.0.push(err);
+                            return;
+                        }
+                    };
+
+                    // In the pot, but not a bet the others have to match
+                    if let Ok((_, _, Some(mut player_stack), ..)) =
+                        players_entities.get_mut(ante_player_entity)
+                    {
+                        player_stack.0 -= ante;
+                    }
+                    match player_antes.get_mut(ante_player_entity) {
+                        Ok(mut player_ante) => player_ante.0 += ante,
+                        Err(_) => {
+                            commands
+                                .entity(ante_player_entity)
+                                .insert(PlayerAnte(*ante));
+                        }
+                    }
+                }
+                Action::PotAwarded(PotAwardedParams { pots }) => {
+                    debug!("Action::PotAwarded from event source");
+
+                    let mut winner_entities = Vec::with_capacity(pots.len());
+                    for PotShare { name, amount } in pots {
+                        let Some(player_entity) = players_hmap.get(name) else {
+                            tracker_errors
+                                .0
+                                .push(TrackerError::UnknownPlayer(name.clone()));
+                            return;
+                        };
+                        winner_entities.push((*player_entity, *amount));
+                    }
+
+                    let pot = hand_pot(&players_entities, &player_antes);
+                    let players = players_entities
+                        .iter()
+                        .filter(|(player_entity, _, player_stack, player_round_bets, ..)| {
+                            let in_pot = player_round_bets.map_or(0, PlayerRoundBets::bets_sum)
+                                + player_ante(&player_antes, *player_entity)
+                                > 0;
+                            let stack = player_stack.map_or(0, |player_stack| player_stack.0);
+                            (stack > 0 && !sitting_out_query.contains(*player_entity)) || in_pot
+                        })
+                        .count();
+                    let awarded = pots.iter().map(|pot_share| pot_share.amount).sum();
+                    if awarded > pot {
+                        tracker_errors
+                            .0
+                            .push(TrackerError::PotOverAwarded { pot, awarded });
+                        return;
+                    }
+                    let rules_rake = rake_rules_query.get(game_entity).ok().map(|rake_rules| {
+                        rake_rules.rake(pot, players, betting_round.street() != Street::Preflop)
+                    });
+                    let hand_rake = HandRake::of_award(pot, awarded, rules_rake);
+                    if let Some(rules_rake) =
+                        rules_rake.filter(|rules_rake| *rules_rake != hand_rake)
+                    {
+                        tracker_errors.0.push(TrackerError::RakeMismatch {
+                            expected: rules_rake.total(),
+                            actual: hand_rake.total(),
+                        });
+                    }
+
+                    // Bets leave the pot, the winners' stacks get what they collected
+                    clear_decisions(&mut players_entities);
+                    players_entities
+                        .iter_mut()
+                        .for_each(|(_, _, _, player_round_bets, ..)| {
+                            if let Some(mut player_round_bets) = player_round_bets {
+                                player_round_bets.0.clear();
+                            }
+                        });
+                    player_antes
+                        .iter_mut()
+                        .for_each(|mut player_ante| player_ante.0 = 0);
+                    winner_entities
+                        .into_iter()
+                        .for_each(|(player_entity, amount)| {
+                            if let Ok((_, _, Some(mut player_stack), ..)) =
+                                players_entities.get_mut(player_entity)
+                            {
+                                player_stack.0 += amount;
+                            }
+                        });
+                    commands.entity(game_entity).insert(hand_rake);
+                }
+                Action::PlayerSatOut(PlayerParams { name }) => {
+                    debug!("Action::PlayerSatOut from event source");
+
+                    let Some(player_entity) = players_hmap.get(name) else {
+                        tracker_errors
+                            .0
+                            .push(TrackerError::UnknownPlayer(name.clone()));
+                        return;
+                    };
+                    commands.entity(*player_entity).insert(PlayerSittingOut);
+                    if let Ok((.., Some(mut need_decision))) =
+                        players_entities.get_mut(*player_entity)
+                    {
+                        need_decision.0 = false;
+                    }
+
+                    let mut active_seats =
+                        active_seats(&players_entities, &sitting_out_query, &eliminated_query);
+                    if let Ok((_, PlayerSeatNum(seat_num), ..)) =
+                        players_entities.get(*player_entity)
+                    {
+                        active_seats.retain(|active_seat_num| active_seat_num != seat_num);
+                    }
+                    reseat_blinds(blind_seats, betting_round, &active_seats);
+                }
+                Action::PlayerSatIn(PlayerParams { name }) => {
+                    debug!("Action::PlayerSatIn from event source");
+
+                    let Some(player_entity) = players_hmap.get(name) else {
+                        tracker_errors
+                            .0
+                            .push(TrackerError::UnknownPlayer(name.clone()));
+                        return;
+                    };
+                    commands.entity(*player_entity).remove::<PlayerSittingOut>();
+
+                    let mut active_seats =
+                        active_seats(&players_entities, &sitting_out_query, &eliminated_query);
+                    if let Ok((_, PlayerSeatNum(seat_num), ..)) =
+                        players_entities.get(*player_entity)
+                    {
+                        active_seats.push(*seat_num);
+                        active_seats.sort_unstable();
+                        active_seats.dedup();
+                    }
+                    reseat_blinds(blind_seats, betting_round, &active_seats);
+                }
+                Action::PlayerLeft(PlayerParams { name }) => {
+                    debug!("Action::PlayerLeft from event source");
+
+                    let Some(player_entity) = players_hmap.remove(name) else {
+                        tracker_errors
+                            .0
+                            .push(TrackerError::UnknownPlayer(name.clone()));
+                        return;
+                    };
+                    players_hmap.retain(|_, other_entity| *other_entity != player_entity);
+
+                    match players_entities.get(player_entity) {
+                        // Bets stay in the pot until the hand is over
+                        Ok((_, _, _, Some(player_round_bets), ..))
+                            if player_round_bets.bets_sum()
+                                + player_ante(&player_antes, player_entity)
+                                > 0 =>
+                        {
+                            commands
+                                .entity(player_entity)
+                                .insert((PlayerSittingOut, PlayerLeaving));
+                        }
+                        _ => commands.entity(player_entity).despawn(),
+                    }
+                }
+                // Handled by `handle_tournament_events`
+                Action::TournamentStarted(_) | Action::BountySet(_) => (),
+            };
+        });
+
+    debug!("Events were handled");
+}
+
+/// The player at `seat_num` who may put `bet_size` in the pot, and their stack.
+/// A player who left may still be there until the hand is over.
+fn bettor(
+    players_entities: &PlayersEntities,
+    sitting_out_query: &SittingOutPlayers,
+    player_names: &Query<&PlayerName>,
+    seat_num: u8,
+    bet_size: u64,
+) -> Result<(Entity, u64), TrackerError> {
+    let player_entity = players_entities
+        .iter()
+        .filter(|(_, &PlayerSeatNum(other_seat_num), ..)| other_seat_num == seat_num)
+        .map(|(player_entity, ..)| player_entity)
+        .min_by_key(|player_entity| sitting_out_query.contains(*player_entity))
+        .ok_or(TrackerError::UnknownSeat(seat_num))?;
+    if sitting_out_query.contains(player_entity) {
+        return Err(TrackerError::PlayerSittingOut(
+            player_names
+                .get(player_entity)
+                .map(|player_name| player_name.0.clone())
+                .unwrap_or_default(),
+        ));
+    }
+
+    let Ok((_, _, Some(player_stack), ..)) = players_entities.get(player_entity) else {
+        return Err(TrackerError::UnknownStack(seat_num));
+    };
+    if bet_size > player_stack.0 {
+        return Err(TrackerError::BetExceedsStack {
+            seat_num,
+            bet_size,
+            stack: player_stack.0,
+        });
+    }
+
+    Ok((player_entity, player_stack.0))
+}
+
+/// Bets and antes of the hand.
+fn hand_pot(players_entities: &PlayersEntities, player_antes: &Query<&mut PlayerAnte>) -> u64 {
+    players_entities
+        .iter()
+        .map(|(player_entity, _, _, player_round_bets, ..)| {
+            player_round_bets.map_or(0, PlayerRoundBets::bets_sum)
+                + player_ante(player_antes, player_entity)
+        })
+        .sum()
+}
+
+fn player_ante(player_antes: &Query<&mut PlayerAnte>, player_entity: Entity) -> u64 {
+    player_antes
+        .get(player_entity)
+        .map_or(0, |player_ante| player_ante.0)
+}
+
+fn seat_states(
+    players_entities: &PlayersEntities,
+    sitting_out_query: &SittingOutPlayers,
+) -> Vec<SeatState> {
+    players_entities
+        .iter()
+        .map(
+            |(
+                player_entity,
+                PlayerSeatNum(seat_num),
+                player_stack,
+                player_round_bets,
+                _,
+                round_betting,
+                _,
+            )| {
+                let bets_sum = player_round_bets.map_or(0, PlayerRoundBets::bets_sum);
+                let sitting_out = sitting_out_query.contains(player_entity);
+                let all_in = player_stack.is_some_and(|player_stack| player_stack.0 == 0)
+                    && bets_sum > 0
+                    && !sitting_out;
+                let can_act = round_betting.is_some_and(|round_betting| round_betting.0)
+                    && player_stack.is_some_and(|player_stack| player_stack.0 > 0)
+                    && !sitting_out;
+
+                SeatState {
+                    seat_num: *seat_num,
+                    can_act,
+                    in_hand: can_act || all_in,
+                    sitting_out,
+                    bets_sum,
+                    stack: player_stack.map_or(0, |player_stack| player_stack.0),
+                }
+            },
+        )
+        .collect()
+}
+
+/// Gives the part of the biggest bet nobody matched back to the bettor and lowers
+/// `RoundMaxBet` to the matched amount. Returns the bettor, their seat and the amount.
+fn return_uncalled_bet(
+    players_entities: &mut PlayersEntities,
+    round_max_bet: &mut u64,
+) -> Option<(Entity, u8, u64)> {
+    let mut bets_sums = players_entities
+        .iter()
+        .map(|(player_entity, _, _, player_round_bets, ..)| {
+            (
+                player_entity,
+                player_round_bets.map_or(0, PlayerRoundBets::bets_sum),
+            )
+        })
+        .collect::<Vec<_>>();
+    bets_sums.sort_by_key(|(_, bets_sum)| Reverse(*bets_sum));
+    let (player_entity, bets_sum) = *bets_sums.first()?;
+    let matched = bets_sums.get(1).map_or(0, |(_, bets_sum)| *bets_sum);
+    let amount = bets_sum - matched;
+    if amount == 0 {
+        return None;
+    }
+
+    let Ok((
+        _,
+        &PlayerSeatNum(seat_num),
+        Some(mut player_stack),
+        Some(mut player_round_bets),
+        _,
+        player_round_betting,
+        _,
+    )) = players_entities.get_mut(player_entity)
+    else {
+        return None;
+    };
+    // Back from all-in, still in the hand though nobody is left to bet against
+    if let (0, Some(mut player_round_betting)) = (player_stack.0, player_round_betting) {
+        player_round_betting.0 = true;
+    }
+    player_stack.0 += amount;
+
+    let mut to_return = amount;
+    while let Some(last_bet) = player_round_bets.0.last_mut().filter(|_| to_return > 0) {
+        let returned = to_return.min(*last_bet);
+        *last_bet -= returned;
+        to_return -= returned;
+        if *last_bet == 0 {
+            player_round_bets.0.pop();
+        }
+    }
+    *round_max_bet = matched;
+
+    Some((player_entity, seat_num, amount))
+}
+
+/// Seats dealt in the next hand, players who sit out, leave or are eliminated are not.
+fn active_seats(
+    players_entities: &PlayersEntities,
+    sitting_out_query: &SittingOutPlayers,
+    eliminated_query: &Query<(), With<PlayerEliminated>>,
+) -> Vec<u8> {
+    let mut active_seats = players_entities
+        .iter()
+        .filter(|(player_entity, ..)| {
+            !sitting_out_query.contains(*player_entity)
+                && !eliminated_query.contains(*player_entity)
+        })
+        .map(|(_, PlayerSeatNum(seat_num), ..)| *seat_num)
+        .collect::<Vec<_>>();
+    active_seats.sort_unstable();
+    active_seats.dedup();
+
+    active_seats
+}
+
+/// Players sitting out or in before the blinds are posted are dealt in or out of the hand,
+/// the blinds follow the button to the seats dealt in.
+fn reseat_blinds(
+    blind_seats: &mut Option<BlindSeats>,
+    betting_round: &BettingRound,
+    active_seats: &[u8],
+) {
+    if !betting_round.is_blind() || betting_round.blinds_posted() > 0 {
+        return;
+    }
+    *blind_seats = blind_seats
+        .and_then(|blind_seats| BlindSeats::from_button(blind_seats.button, active_seats));
+}
+
+fn clear_decisions(players_entities: &mut PlayersEntities) {
+    players_entities
+        .iter_mut()
+        .for_each(|(.., need_decision)| match need_decision {
+            Some(mut need_decision) if need_decision.0 => need_decision.0 = false,
+            _ => (),
+        });
+}
+
+/// Only the tracked (npc) player gets asked.
+fn ask_for_decision(
+    commands: &mut Commands,
+    players_entities: &mut PlayersEntities,
+    seat_num: u8,
+    bet_range: Option<BetRange>,
+) {
+    if let Some((player_entity, _, _, _, Some(_), _, Some(mut need_decision))) = players_entities
+        .iter_mut()
+        .find(|(_, PlayerSeatNum(other_seat_num), ..)| *other_seat_num == seat_num)
+    {
+        need_decision.0 = true;
+        if let Some(bet_range) = bet_range {
+            commands
+                .entity(player_entity)
+                .insert(PlayerBetRange(bet_range));
+        }
+    }
+}
+
+/// No limit until the game type is known.
+fn game_type(game_type_query: &Query<&GameType>) -> GameType {
+    game_type_query
+        .iter()
+        .next()
+        .copied()
+        .unwrap_or(GameType::NL)
+}
@@ -0,0 +1,168 @@
+use std::f32::consts::PI;
+use std::io::{self, Write};
+
+use bevy::prelude::*;
+
+use crate::action::cards_line;
+use crate::view::{PlayerView, TableView};
+
+const CANVAS_WIDTH: usize = 80;
+const CANVAS_HEIGHT: usize = 23;
+const DEFAULT_MAX_SEATS: u8 = 6;
+
+/// Redraws the whole terminal from `TableView` when it changed in this frame.
+/// Owns the screen, so only for a single table: several tables are drawn together
+/// by `render_tables`, see `HandHistoryWatcher`.
+pub fn terminal_ui_system(table_view: Res<TableView>) {
+    if !table_view.is_changed() {
+        return;
+    }
+
+    draw_screen(&render_table(&table_view));
+}
+
+/// Clears the screen and moves the cursor home, so the tables stay in place.
+pub fn draw_screen(screen: &str) {
+    let mut stdout = io::stdout().lock();
+    let _ = write!(stdout, "\x1b[2J\x1b[H{screen}");
+    let _ = stdout.flush();
+}
+
+/// Tables one below the other, each under its title.
+pub fn render_tables<'a>(tables: impl IntoIterator<Item = (&'a str, &'a TableView)>) -> String {
+    tables
+        .into_iter()
+        .map(|(title, table_view)| format!("== {title} ==\n{}", render_table(table_view)))
+        .collect()
+}
+
+/// Draws the seats as a ring around the board, seat 1 at the bottom, clockwise.
+pub fn render_table(table_view: &TableView) -> String {
+    let mut canvas = Canvas::new(CANVAS_WIDTH, CANVAS_HEIGHT);
+
+    let max_seats = table_view
+        .players
+        .iter()
+        .map(|player| player.seat_num)
+        .chain(table_view.max_seats)
+        .max()
+        .unwrap_or(DEFAULT_MAX_SEATS);
+
+    let center_x = CANVAS_WIDTH as f32 / 2.0;
+    let center_y = CANVAS_HEIGHT as f32 / 2.0 - 1.0;
+    let radius_x = CANVAS_WIDTH as f32 / 2.0 - 10.0;
+    let radius_y = CANVAS_HEIGHT as f32 / 2.0 - 3.0;
+
+    (1..=max_seats).for_each(|seat_num| {
+        let angle = PI / 2.0 + 2.0 * PI * f32::from(seat_num - 1) / f32::from(max_seats);
+        let x = (center_x + radius_x * angle.cos()).round() as usize;
+        let y = (center_y + radius_y * angle.sin()).round() as usize;
+
+        let seat_lines = match table_view
+            .players
+            .iter()
+            .find(|player| player.seat_num == seat_num)
+        {
+            Some(player) => seat_lines(player),
+            None => vec![format!("[{seat_num}] empty")],
+        };
+        seat_lines
+            .iter()
+            .enumerate()
+            .for_each(|(line_index, line)| canvas.put_centered(x, y + line_index, line));
+    });
+
+    let center_x = center_x as usize;
+    let center_y = center_y as usize;
+    canvas.put_centered(
+        center_x,
+        center_y - 1,
+        &format!(
+            "#{} {} {} {}",
+            table_view.hand_id.as_deref().unwrap_or("-"),
+            table_view.game_variant.unwrap_or_default().name(),
+            table_view
+                .game_type
+                .map_or_else(String::new, |game_type| format!("{game_type:?}")),
+            table_view
+                .game_limit
+                .map_or_else(String::new, |game_limit| game_limit.to_string()),
+        ),
+    );
+    canvas.put_centered(
+        center_x,
+        center_y,
+        &format!("{:?}  {}", table_view.street, cards_line(&table_view.board)),
+    );
+    canvas.put_centered(center_x, center_y + 1, &format!("pot {}", table_view.pot));
+    if let Some(player) = table_view
+        .players
+        .iter()
+        .find(|player| player.need_decision)
+    {
+        canvas.put_centered(center_x, center_y + 2, &format!("{} to act", player.name));
+    }
+
+    canvas.render()
+}
+
+fn seat_lines(player: &PlayerView) -> Vec<String> {
+    let marker = if player.need_decision { ">" } else { " " };
+    let button = if player.dealer { " (D)" } else { "" };
+
+    let mut lines = vec![
+        format!("{marker}[{}] {}{button}", player.seat_num, player.name),
+        format!(
+            "{} {:?}",
+            player
+                .stack
+                .map_or_else(|| "-".to_string(), |stack| stack.to_string()),
+            player.status,
+        ),
+    ];
+    if player.round_bets_sum() > 0 {
+        lines.push(format!("bet {}", player.round_bets_sum()));
+    }
+    if !player.cards.is_empty() {
+        lines.push(cards_line(&player.cards));
+    }
+
+    lines
+}
+
+struct Canvas {
+    width: usize,
+    rows: Vec<Vec<char>>,
+}
+
+impl Canvas {
+    fn new(width: usize, height: usize) -> Self {
+        Canvas {
+            width,
+            rows: vec![vec![' '; width]; height],
+        }
+    }
+
+    fn put_centered(&mut self, center_x: usize, y: usize, text: &str) {
+        let Some(row) = self.rows.get_mut(y) else {
+            return;
+        };
+
+        let text_len = text.chars().count();
+        let start_x = center_x
+            .saturating_sub(text_len / 2)
+            .min(self.width.saturating_sub(text_len));
+        text.chars()
+            .zip(row.iter_mut().skip(start_x))
+            .for_each(|(char, cell)| *cell = char);
+    }
+
+    fn render(&self) -> String {
+        self.rows
+            .iter()
+            .map(|row| row.iter().collect::<String>().trim_end().to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+            + "\n"
+    }
+}
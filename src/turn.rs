@@ -0,0 +1,42 @@
+/// Occupied seats in clockwise order: by seat number, wrapping after the highest one.
+/// Seat numbers don't have to be contiguous, empty seats are simply not there.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TurnOrder {
+    /// `(seat_num, can_act)` sorted by seat number.
+    seats: Vec<(u8, bool)>,
+}
+
+impl TurnOrder {
+    /// `can_act` is false for folded and all-in players.
+    pub fn new(seats: impl IntoIterator<Item = (u8, bool)>) -> Self {
+        let mut seats = seats.into_iter().collect::<Vec<_>>();
+        seats.sort_by_key(|(seat_num, _)| *seat_num);
+
+        TurnOrder { seats }
+    }
+
+    /// Occupied seats clockwise after `seat_num`, which doesn't have to be occupied itself.
+    pub fn after(&self, seat_num: u8) -> impl Iterator<Item = u8> + '_ {
+        let split_index = self
+            .seats
+            .partition_point(|(other_seat_num, _)| *other_seat_num <= seat_num);
+
+        self.seats[split_index..]
+            .iter()
+            .chain(&self.seats[..split_index])
+            .map(|(other_seat_num, _)| *other_seat_num)
+            .filter(move |other_seat_num| *other_seat_num != seat_num)
+    }
+
+    /// The next player after `seat_num` who can still bet.
+    pub fn next_to_act(&self, seat_num: u8) -> Option<u8> {
+        self.after(seat_num)
+            .find(|other_seat_num| self.can_act(*other_seat_num))
+    }
+
+    pub fn can_act(&self, seat_num: u8) -> bool {
+        self.seats
+            .iter()
+            .any(|(other_seat_num, can_act)| *other_seat_num == seat_num && *can_act)
+    }
+}
@@ -0,0 +1,144 @@
+use bevy::ecs::event::{Events, ManualEventReader};
+use bevy::ecs::query::QuerySingleError;
+use bevy::prelude::*;
+
+use crate::action::*;
+use crate::components::*;
+use crate::error::{TrackerError, TrackerErrors};
+
+pub fn read_parser_events_for_validation(
+    event_source: Res<Events<Action>>,
+    mut event_reader: Local<Option<ManualEventReader<Action>>>,
+) -> Vec<Action> {
+    event_reader
+        .get_or_insert_with(|| event_source.get_reader())
+        .iter(&event_source)
+        .map(|event| match event {
+            Action::GameHandIdSet(_) => {
+                debug!("Validation Action::GameHandIdSet from event source");
+                Action::GameHandIdSet("111111".into())
+            }
+            Action::GameDealerSeatNumSet(_) => {
+                debug!("Validation Action::GameDealerSeatNumSet from event source");
+                Action::GameDealerSeatNumSet(1)
+            }
+            _ => event.clone(),
+        })
+        .collect::<Vec<_>>()
+}
+
+pub fn validate_board_cards(
+    In(actions): In<Vec<Action>>,
+    players_cards_entities: Query<&PlayerCards>,
+    board_cards_entities: Query<&BoardCards>,
+    mut tracker_errors: ResMut<TrackerErrors>,
+) -> Vec<Action> {
+    actions.iter().for_each(|action| {
+        match action {
+            Action::CommunityCardsDealt(CommunityCardsDealtParams {
+                new_cards: cards, ..
+            })
+            | Action::NpcCardsDealt(NpcCardsDealtParams { cards, .. }) => {
+                debug!("Validator validate_board_cards - {actions:?}");
+
+                let board_cards = match board_cards_entities.get_single() {
+                    Ok(board_cards_entity) => board_cards_entity.0.clone(),
+                    Err(QuerySingleError::MultipleEntities(_)) => {
+                        panic!("BoardCards should be single entity")
+                    }
+                    _ => vec![],
+                };
+
+                let all_known_cards = players_cards_entities.iter().fold(
+                    board_cards,
+                    |mut all_cards, player_cards| {
+                        all_cards.extend(player_cards.0.clone().into_iter());
+                        all_cards
+                    },
+                );
+
+                debug!("Validator validate_board_cards - {all_known_cards:?} - all_known_cards");
+                let duplicate_cards = cards
+                    .iter()
+                    .filter(|card| all_known_cards.contains(card))
+                    .copied()
+                    .collect::<Vec<_>>();
+                if !duplicate_cards.is_empty() {
+                    debug!("Validator validate_board_cards - {action:?} - has bad cards");
+                    tracker_errors
+                        .0
+                        .push(TrackerError::DuplicateCards(duplicate_cards));
+                }
+            }
+            _ => (),
+        };
+    });
+
+    actions
+}
+
+pub fn validate_hole_cards(
+    In(actions): In<Vec<Action>>,
+    game_variant_query: Query<&GameVariant>,
+    mut tracker_errors: ResMut<TrackerErrors>,
+) -> Vec<Action> {
+    let game_variant = game_variant(&actions, &game_variant_query);
+
+    actions.iter().for_each(|action| {
+        if let Action::NpcCardsDealt(NpcCardsDealtParams { name, cards }) = action {
+            if cards.len() != game_variant.hole_cards() {
+                debug!("Validator validate_hole_cards - {action:?} - has wrong cards count");
+                tracker_errors.0.push(TrackerError::WrongHoleCards {
+                    name: name.clone(),
+                    expected: game_variant.hole_cards(),
+                    actual: cards.len(),
+                });
+            }
+        }
+    });
+
+    actions
+}
+
+pub fn validate_deck_cards(
+    In(actions): In<Vec<Action>>,
+    game_variant_query: Query<&GameVariant>,
+    mut tracker_errors: ResMut<TrackerErrors>,
+) -> Vec<Action> {
+    let deck = game_variant(&actions, &game_variant_query).deck();
+
+    actions.iter().for_each(|action| match action {
+        Action::CommunityCardsDealt(CommunityCardsDealtParams {
+            new_cards: cards, ..
+        })
+        | Action::NpcCardsDealt(NpcCardsDealtParams { cards, .. }) => {
+            let missing_cards = cards
+                .iter()
+                .filter(|card| !deck.contains(card))
+                .copied()
+                .collect::<Vec<_>>();
+            if !missing_cards.is_empty() {
+                debug!("Validator validate_deck_cards - {action:?} - has cards out of deck");
+                tracker_errors
+                    .0
+                    .push(TrackerError::CardsNotInDeck(missing_cards));
+            }
+        }
+        _ => (),
+    });
+
+    actions
+}
+
+/// Variant set in the same frame as the cards or earlier.
+fn game_variant(actions: &[Action], game_variant_query: &Query<&GameVariant>) -> GameVariant {
+    actions
+        .iter()
+        .rev()
+        .find_map(|action| match action {
+            Action::GameVariantSet(game_variant) => Some(*game_variant),
+            _ => None,
+        })
+        .or_else(|| game_variant_query.iter().next().copied())
+        .unwrap_or_default()
+}
@@ -0,0 +1,329 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::action::Card;
+use crate::betting::BetRange;
+use crate::components::*;
+use crate::rake::HandRake;
+use crate::tracker::TrackerState;
+
+/// Plain-data copy of the table for code that should not touch the ECS.
+/// Rebuilt by `update_table_view_system` after the actions of a frame are applied.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TableView {
+    pub hand_id: Option<String>,
+    pub game_type: Option<GameType>,
+    pub game_variant: Option<GameVariant>,
+    pub game_limit: Option<GameLimit>,
+    pub max_seats: Option<u8>,
+    pub table_name: Option<String>,
+    pub dealer_seat_num: Option<u8>,
+    /// Predicted from the previous hand or the dealer seat of this one.
+    pub blind_seats: Option<BlindSeats>,
+    pub street: Street,
+    pub board: Vec<Card>,
+    pub pot: u64,
+    pub round_max_bet: u64,
+    /// Taken from the pot once it was awarded, until the next hand.
+    pub rake: Option<HandRake>,
+    pub players: Vec<PlayerView>,
+    pub tournament: Option<TournamentView>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TournamentView {
+    pub tournament_id: String,
+    /// Index in the blind schedule.
+    pub level: usize,
+    pub blind_level: Option<BlindLevel>,
+    pub hands: usize,
+    pub eliminations: Vec<Elimination>,
+    pub payouts: Vec<u64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayerView {
+    pub name: String,
+    pub seat_num: u8,
+    pub position: Option<Position>,
+    pub status: PlayerStatus,
+    pub npc: bool,
+    pub dealer: bool,
+    pub stack: Option<u64>,
+    pub round_bets: Vec<u64>,
+    /// Posted this hand, in the pot but not in `round_bets`.
+    pub ante: u64,
+    pub cards: Vec<Card>,
+    pub need_decision: bool,
+    /// Only known while the player needs a decision.
+    pub bet_range: Option<BetRange>,
+    pub bounty: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Street {
+    #[default]
+    Preflop,
+    Flop,
+    Turn,
+    River,
+}
+
+impl Street {
+    pub fn from_board(board: &[Card]) -> Self {
+        match board.len() {
+            0..=2 => Street::Preflop,
+            3 => Street::Flop,
+            4 => Street::Turn,
+            _ => Street::River,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Position {
+    Button,
+    SmallBlind,
+    BigBlind,
+    UnderTheGun,
+    Middle,
+    Hijack,
+    Cutoff,
+}
+
+impl Position {
+    /// `offset` counts seats clockwise from the button among `players` seated players.
+    pub fn from_button_offset(offset: usize, players: usize) -> Self {
+        if players == 2 {
+            return if offset == 0 {
+                Position::Button
+            } else {
+                Position::BigBlind
+            };
+        }
+
+        match offset {
+            0 => Position::Button,
+            1 => Position::SmallBlind,
+            2 => Position::BigBlind,
+            _ if offset + 1 == players => Position::Cutoff,
+            _ if offset + 2 == players => Position::Hijack,
+            3 => Position::UnderTheGun,
+            _ => Position::Middle,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PlayerStatus {
+    /// Seated, stack is not known yet.
+    #[default]
+    Waiting,
+    Active,
+    Folded,
+    AllIn,
+    /// Out of the tournament.
+    Eliminated,
+    /// Keeps the seat, but is not dealt in.
+    SittingOut,
+}
+
+impl PlayerView {
+    pub fn round_bets_sum(&self) -> u64 {
+        self.round_bets.iter().sum()
+    }
+
+    /// Chips put in the pot this hand, the ante included.
+    pub fn committed(&self) -> u64 {
+        self.round_bets_sum() + self.ante
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn update_table_view_system(
+    mut table_view: ResMut<TableView>,
+    tracker_state: Res<TrackerState>,
+    game_query: Query<
+        (
+            Option<&GameHandId>,
+            Option<&GameType>,
+            Option<&GameVariant>,
+            Option<&GameLimit>,
+            Option<&GameMaxSeats>,
+            Option<&GameTableName>,
+            Option<&DealerSeatNum>,
+            Option<&HandRake>,
+        ),
+        With<Game>,
+    >,
+    tournament_query: Query<
+        (
+            &TournamentId,
+            &BlindSchedule,
+            &TournamentLevel,
+            &TournamentHands,
+            &TournamentEliminations,
+            &TournamentPayouts,
+        ),
+        With<Tournament>,
+    >,
+    board_query: Query<&BoardCards, With<Board>>,
+    round_max_bet_query: Query<&RoundMaxBet>,
+    players_query: Query<
+        (
+            &PlayerName,
+            &PlayerSeatNum,
+            Option<&PlayerNpc>,
+            Option<&Dealer>,
+            Option<&PlayerStack>,
+            Option<&PlayerRoundBets>,
+            Option<&PlayerAnte>,
+            Option<&PlayerCards>,
+            Option<&PlayerRoundBetting>,
+            Option<&NeedDecision>,
+            Option<&PlayerBetRange>,
+            Option<&PlayerBounty>,
+            Option<&PlayerEliminated>,
+            Option<&PlayerSittingOut>,
+        ),
+        With<Player>,
+    >,
+) {
+    let mut new_table_view = TableView::default();
+
+    if let Some((
+        hand_id,
+        game_type,
+        game_variant,
+        game_limit,
+        max_seats,
+        table_name,
+        dealer_seat_num,
+        hand_rake,
+    )) = game_query.iter().next()
+    {
+        new_table_view.hand_id = hand_id.map(|hand_id| hand_id.0.clone());
+        new_table_view.game_type = game_type.copied();
+        new_table_view.game_variant = game_variant.copied();
+        new_table_view.game_limit = game_limit.copied();
+        new_table_view.max_seats = max_seats.map(|max_seats| max_seats.0);
+        new_table_view.table_name = table_name.map(|table_name| table_name.0.clone());
+        new_table_view.dealer_seat_num = dealer_seat_num.map(|dealer_seat_num| dealer_seat_num.0);
+        new_table_view.rake = hand_rake.copied();
+    }
+    new_table_view.blind_seats = tracker_state.blind_seats;
+
+    new_table_view.tournament = tournament_query.iter().next().map(
+        |(tournament_id, schedule, level, hands, eliminations, payouts)| TournamentView {
+            tournament_id: tournament_id.0.clone(),
+            level: level.index,
+            blind_level: schedule.level(level.index),
+            hands: hands.0.len(),
+            eliminations: eliminations.0.clone(),
+            payouts: payouts.0.clone(),
+        },
+    );
+
+    new_table_view.board = board_query
+        .iter()
+        .next()
+        .map(|board_cards| board_cards.0.clone())
+        .unwrap_or_default();
+    new_table_view.street = Street::from_board(&new_table_view.board);
+
+    new_table_view.round_max_bet = round_max_bet_query
+        .iter()
+        .next()
+        .map_or(0, |round_max_bet| round_max_bet.0);
+
+    new_table_view.players = players_query
+        .iter()
+        .map(
+            |(
+                name,
+                seat_num,
+                npc,
+                dealer,
+                stack,
+                round_bets,
+                ante,
+                cards,
+                round_betting,
+                need_decision,
+                bet_range,
+                bounty,
+                eliminated,
+                sitting_out,
+            )| {
+                let round_bets =
+                    round_bets.map_or_else(Vec::new, |round_bets| round_bets.0.clone());
+                let ante = ante.map_or(0, |ante| ante.0);
+                // A busted player waiting for chips has nothing in the pot
+                let committed = round_bets.iter().sum::<u64>() + ante > 0;
+                let status = match (stack, round_betting) {
+                    _ if eliminated.is_some() => PlayerStatus::Eliminated,
+                    _ if sitting_out.is_some() => PlayerStatus::SittingOut,
+                    (None, _) => PlayerStatus::Waiting,
+                    (Some(PlayerStack(0)), _) if committed => PlayerStatus::AllIn,
+                    (_, Some(PlayerRoundBetting(false))) => PlayerStatus::Folded,
+                    _ => PlayerStatus::Active,
+                };
+
+                PlayerView {
+                    name: name.0.clone(),
+                    seat_num: seat_num.0,
+                    position: None,
+                    status,
+                    npc: npc.is_some(),
+                    dealer: dealer.is_some(),
+                    stack: stack.map(|stack| stack.0),
+                    round_bets,
+                    ante,
+                    cards: cards.map_or_else(Vec::new, |cards| cards.0.clone()),
+                    need_decision: need_decision.is_some_and(|need_decision| need_decision.0),
+                    bet_range: bet_range
+                        .filter(|_| need_decision.is_some_and(|need_decision| need_decision.0))
+                        .map(|bet_range| bet_range.0),
+                    bounty: bounty.map(|bounty| bounty.0),
+                }
+            },
+        )
+        .collect();
+    new_table_view.players.sort_by_key(|player| player.seat_num);
+
+    new_table_view.pot = new_table_view
+        .players
+        .iter()
+        .map(PlayerView::committed)
+        .sum();
+
+    // Players sitting out have no position, the blinds pass them by
+    let mut dealt_players = new_table_view
+        .players
+        .iter_mut()
+        .filter(|player| player.status != PlayerStatus::SittingOut)
+        .collect::<Vec<_>>();
+    if let Some(dealer_seat_num) = new_table_view.dealer_seat_num {
+        // A dead button keeps its place in the ring, the players after it are the blinds
+        let dead_button = dealt_players
+            .iter()
+            .all(|player| player.seat_num != dealer_seat_num);
+        let button_index = dealt_players
+            .iter()
+            .filter(|player| player.seat_num < dealer_seat_num)
+            .count();
+        let ring_len = dealt_players.len() + usize::from(dead_button);
+        dealt_players
+            .iter_mut()
+            .enumerate()
+            .for_each(|(index, player)| {
+                let ring_index = index + usize::from(dead_button && index >= button_index);
+                let offset = (ring_index + ring_len - button_index) % ring_len;
+                player.position = Some(Position::from_button_offset(offset, ring_len));
+            });
+    }
+
+    if *table_view != new_table_view {
+        *table_view = new_table_view;
+    }
+}
@@ -0,0 +1,222 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+
+use crate::action::Action;
+use crate::error::{TrackerError, TrackerErrors};
+use crate::hand_history_parser::{take_complete_hands, HandHistoryParser};
+use crate::plugin::{DisplayMode, PokerTrackerPlugin};
+use crate::session::TableSession;
+use crate::tui::{draw_screen, render_tables};
+
+/// Input plugin: tails the hand history file at `path` and sends its finished hands
+/// into `Events<Action>`, one batch per frame. Read and parse failures go to `TrackerErrors`.
+#[derive(Debug, Clone)]
+pub struct HandHistoryFilePlugin {
+    pub path: PathBuf,
+}
+
+impl Plugin for HandHistoryFilePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(TailedFile::new(self.path.clone()))
+            .add_system(tail_hand_history_system.in_base_set(CoreSet::First));
+    }
+}
+
+/// Read position in the file and the parsed batches not sent yet.
+#[derive(Resource, Debug)]
+pub struct TailedFile {
+    path: PathBuf,
+    offset: u64,
+    unread: Vec<u8>,
+    text: String,
+    parser: HandHistoryParser,
+    batches: VecDeque<Vec<Action>>,
+}
+
+impl TailedFile {
+    fn new(path: PathBuf) -> Self {
+        TailedFile {
+            path,
+            offset: 0,
+            unread: default(),
+            text: default(),
+            parser: default(),
+            batches: default(),
+        }
+    }
+
+    /// Batches parsed from the file and still waiting for their frame.
+    pub fn pending_batches(&self) -> usize {
+        self.batches.len()
+    }
+}
+
+/// Reads the file once every parsed batch was sent, then sends the next batch.
+pub fn tail_hand_history_system(
+    mut tailed_file: ResMut<TailedFile>,
+    mut action_writer: EventWriter<Action>,
+    mut tracker_errors: ResMut<TrackerErrors>,
+) {
+    if tailed_file.batches.is_empty() {
+        if let Err(err) = read_appended(&mut tailed_file) {
+            tracker_errors
+                .0
+                .push(TrackerError::HandHistoryUnreadable(format!(
+                    "{:?} - {err}",
+                    tailed_file.path
+                )));
+        }
+
+        for hand in take_complete_hands(&mut tailed_file.text) {
+            match tailed_file.parser.parse_hand(&hand) {
+                Ok(batches) => tailed_file.batches.extend(batches),
+                Err(err) => tracker_errors.0.push(TrackerError::HandSkipped(format!(
+                    "{:?} - {err}",
+                    tailed_file.path
+                ))),
+            }
+        }
+    }
+
+    if let Some(batch) = tailed_file.batches.pop_front() {
+        action_writer.send_batch(batch);
+    }
+}
+
+/// Watches the `.txt` files of `dir`. Every file is a table of its own,
+/// a `TableSession` fed by its `HandHistoryFilePlugin`.
+/// The terminal UI draws all tables on one screen instead of one per session.
+pub struct HandHistoryWatcher {
+    dir: PathBuf,
+    plugin: PokerTrackerPlugin,
+    terminal_ui: bool,
+    /// Last drawn by the terminal UI.
+    screen: String,
+    sessions: HashMap<PathBuf, TableSession>,
+}
+
+impl HandHistoryWatcher {
+    /// Sessions of new files are built with `plugin`.
+    pub fn new(dir: PathBuf, plugin: PokerTrackerPlugin) -> Self {
+        let terminal_ui = plugin.display == DisplayMode::Terminal;
+        HandHistoryWatcher {
+            dir,
+            plugin: PokerTrackerPlugin {
+                display: if terminal_ui {
+                    DisplayMode::Off
+                } else {
+                    plugin.display
+                },
+                ..plugin
+            },
+            terminal_ui,
+            screen: default(),
+            sessions: default(),
+        }
+    }
+
+    /// Runs frames of every session until all hands appended since the last poll
+    /// are applied. Sessions of files gone from `dir` are dropped.
+    /// Returns what the sessions reported, file by file.
+    pub fn poll(&mut self) -> io::Result<Vec<(PathBuf, TrackerError)>> {
+        let mut paths = fs::read_dir(&self.dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|extension| extension == "txt"))
+            .collect::<Vec<_>>();
+        paths.sort();
+        self.sessions.retain(|path, _| paths.contains(path));
+
+        let mut reports = vec![];
+        for path in paths {
+            let table_session = self.sessions.entry(path.clone()).or_insert_with(|| {
+                let mut table_session = TableSession::with_plugin(self.plugin.clone());
+                table_session.add_plugin(HandHistoryFilePlugin { path: path.clone() });
+                table_session
+            });
+
+            loop {
+                let errors = match table_session.apply([]) {
+                    Ok(outcome) => outcome.warnings,
+                    Err(tracker_errors) => tracker_errors.0,
+                };
+                reports.extend(errors.into_iter().map(|err| (path.clone(), err)));
+
+                let pending_batches = table_session
+                    .resource::<TailedFile>()
+                    .map_or(0, TailedFile::pending_batches);
+                if pending_batches == 0 {
+                    break;
+                }
+            }
+        }
+
+        if self.terminal_ui {
+            self.draw_tables();
+        }
+
+        Ok(reports)
+    }
+
+    fn draw_tables(&mut self) {
+        let mut tables = self
+            .sessions
+            .iter()
+            .map(|(path, table_session)| {
+                let title = path
+                    .file_stem()
+                    .map_or_else(String::new, |file_stem| file_stem.to_string_lossy().into());
+                (title, table_session.table_view())
+            })
+            .collect::<Vec<_>>();
+        tables.sort_by(|(title, _), (other_title, _)| title.cmp(other_title));
+
+        let screen = render_tables(
+            tables
+                .iter()
+                .map(|(title, table_view)| (title.as_str(), table_view)),
+        );
+        if screen != self.screen {
+            draw_screen(&screen);
+            self.screen = screen;
+        }
+    }
+
+    /// Session tracking the table of the file `path`, while the file exists.
+    pub fn session(&self, path: &Path) -> Option<&TableSession> {
+        self.sessions.get(path)
+    }
+}
+
+fn read_appended(tailed_file: &mut TailedFile) -> io::Result<()> {
+    let mut file = File::open(&tailed_file.path)?;
+    let file_len = file.metadata()?.len();
+    if file_len < tailed_file.offset {
+        // Truncated or replaced, start over
+        tailed_file.offset = 0;
+        tailed_file.unread.clear();
+        tailed_file.text.clear();
+    }
+    if file_len == tailed_file.offset {
+        return Ok(());
+    }
+
+    file.seek(SeekFrom::Start(tailed_file.offset))?;
+    let read_len = file.read_to_end(&mut tailed_file.unread)?;
+    tailed_file.offset += read_len as u64;
+
+    // Keep an incomplete UTF-8 sequence at the end for the next read
+    let valid_len = match std::str::from_utf8(&tailed_file.unread) {
+        Ok(_) => tailed_file.unread.len(),
+        Err(err) if err.error_len().is_none() => err.valid_up_to(),
+        Err(_) => tailed_file.unread.len(),
+    };
+    let text = String::from_utf8_lossy(&tailed_file.unread[..valid_len]).replace("\r\n", "\n");
+    tailed_file.text.push_str(&text);
+    tailed_file.unread.drain(..valid_len);
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+//! Fixtures and action builders shared by the integration tests.
+// Every test crate uses only some of them
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::Path;
+
+use ecs_example::*;
+
+/// Text of a hand history file from `tests/hand_histories`.
+pub fn hand_history(file_name: &str) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/hand_histories")
+        .join(file_name);
+    fs::read_to_string(&path).unwrap_or_else(|err| panic!("Can't read {path:?}: {err}"))
+}
+
+pub fn seat(name: &str, seat_num: u8) -> Action {
+    Action::SeatUpdated(SeatUpdatedParams {
+        name: name.into(),
+        seat_num,
+        npc: false,
+    })
+}
+
+pub fn stack(name: &str, stack: u64) -> Action {
+    Action::StackUpdated(StackUpdatedParams {
+        name: name.into(),
+        stack,
+    })
+}
+
+pub fn bet(seat_num: u8, bet_size: u64) -> Action {
+    Action::BetMade(BetMadeParams {
+        seat_index: seat_num,
+        bet_size,
+    })
+}
+
+/// The whole pot to one player.
+pub fn pot_awarded(name: &str, amount: u64) -> Action {
+    Action::PotAwarded(PotAwardedParams {
+        pots: vec![PotShare {
+            name: name.into(),
+            amount,
+        }],
+    })
+}
@@ -0,0 +1,154 @@
+use ecs_example::equity::{equity, DEFAULT_SAMPLES};
+use ecs_example::evaluator::{best_hand, evaluate_five, HandCategory};
+use ecs_example::*;
+
+fn cards(cards: &str) -> Vec<Card> {
+    cards
+        .split_whitespace()
+        .map(|card| Card::parse(card).expect("Bad card"))
+        .collect()
+}
+
+fn five(hand: &str) -> [Card; 5] {
+    cards(hand).try_into().expect("Expected five cards")
+}
+
+#[test]
+fn five_card_categories() {
+    let hands = [
+        ("Ah Kh Qh Jh Th", HandCategory::StraightFlush),
+        ("9c 9d 9h 9s 2c", HandCategory::FourOfAKind),
+        ("3c 3d 3h 8s 8c", HandCategory::FullHouse),
+        ("2d 7d 9d Jd Kd", HandCategory::Flush),
+        ("Ah 2c 3d 4s 5h", HandCategory::Straight),
+        ("Qc Qd Qh 4s 2c", HandCategory::ThreeOfAKind),
+        ("Jc Jd 4h 4s 2c", HandCategory::TwoPair),
+        ("Tc Td 8h 4s 2c", HandCategory::Pair),
+        ("Ac Jd 8h 4s 2c", HandCategory::HighCard),
+    ];
+
+    for (hand, category) in hands {
+        assert_eq!(
+            evaluate_five(GameVariant::Holdem, five(hand)).category,
+            category,
+            "{hand}"
+        );
+    }
+}
+
+#[test]
+fn ties_and_kickers() {
+    assert!(
+        evaluate_five(GameVariant::Holdem, five("Ac Ad Kh 4s 2c"))
+            > evaluate_five(GameVariant::Holdem, five("Ah As Qh 4d 3c"))
+    );
+    assert!(
+        evaluate_five(GameVariant::Holdem, five("2c 3d 4h 5s 6c"))
+            > evaluate_five(GameVariant::Holdem, five("Ah 2c 3d 4s 5h"))
+    );
+    assert_eq!(
+        evaluate_five(GameVariant::Holdem, five("Ac Kd Qh Js 9c")),
+        evaluate_five(GameVariant::Holdem, five("Ad Kc Qs Jh 9d"))
+    );
+}
+
+#[test]
+fn omaha_uses_exactly_two_hole_cards() {
+    let board = cards("Ah Kh Qh 2h 3d");
+
+    // One heart in hand makes no flush in Omaha, any five cards play in Hold'em
+    let hole_cards = cards("Jh 7c 7d 8s");
+    assert_eq!(
+        best_hand(GameVariant::Omaha, &hole_cards, &board).map(|rank| rank.category),
+        Some(HandCategory::Pair)
+    );
+    assert_eq!(
+        best_hand(GameVariant::Holdem, &hole_cards[..2], &board).map(|rank| rank.category),
+        Some(HandCategory::Flush)
+    );
+
+    // Four aces in five card Omaha still play as trips
+    let hole_cards = cards("Ac Ad As 5c 6c");
+    assert_eq!(
+        best_hand(GameVariant::Omaha5, &hole_cards, &board).map(|rank| rank.category),
+        Some(HandCategory::ThreeOfAKind)
+    );
+
+    assert_eq!(
+        best_hand(GameVariant::Omaha, &hole_cards[..4], &board[..2]),
+        None
+    );
+}
+
+#[test]
+fn equity_of_made_and_drawing_hands() {
+    // Hearts but the 3h and Kh, which improve the kings too: 7 outs of 44 cards
+    let shares = equity(
+        GameVariant::Holdem,
+        &[cards("Kc Kd"), cards("Ah Qh")],
+        &cards("Ks 7h 2h 3c"),
+        DEFAULT_SAMPLES,
+        1,
+    );
+    assert!((shares[1] - 7.0 / 44.0).abs() < 1e-9, "{shares:?}");
+    assert!((shares.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+
+    // Same hole cards split the pot
+    let shares = equity(
+        GameVariant::Omaha,
+        &[cards("Ac Kc Qd Jd"), cards("Ah Kh Qs Js")],
+        &cards("2c 7d 9s"),
+        DEFAULT_SAMPLES,
+        1,
+    );
+    assert!(shares.iter().all(|share| *share > 0.4), "{shares:?}");
+
+    let shares = equity(
+        GameVariant::Omaha,
+        &[cards("Ac Ad Kc Kd"), cards("7h 8h 2s 3s")],
+        &[],
+        2_000,
+        7,
+    );
+    assert!(shares[0] > 0.6 && shares[0] < 0.9, "{shares:?}");
+}
+
+#[test]
+fn short_deck_ranking() {
+    let flush = five("6h 8h Th Qh Ah");
+    let full_house = five("9c 9d 9h Ks Kc");
+    assert!(
+        evaluate_five(GameVariant::Holdem, full_house) > evaluate_five(GameVariant::Holdem, flush)
+    );
+    assert!(
+        evaluate_five(GameVariant::ShortDeck, flush)
+            > evaluate_five(GameVariant::ShortDeck, full_house)
+    );
+
+    let ace_to_nine = five("Ah 6c 7d 8s 9h");
+    assert_eq!(
+        evaluate_five(GameVariant::ShortDeck, ace_to_nine).category,
+        HandCategory::Straight
+    );
+    assert_eq!(
+        evaluate_five(GameVariant::Holdem, ace_to_nine).category,
+        HandCategory::HighCard
+    );
+    assert!(
+        evaluate_five(GameVariant::ShortDeck, five("6h 7c 8d 9s Th"))
+            > evaluate_five(GameVariant::ShortDeck, ace_to_nine)
+    );
+}
+
+#[test]
+fn short_deck_equity_deals_from_36_cards() {
+    // 36 cards less 2 hands and the turn leave 28 rivers, the nines outs are 2
+    let shares = equity(
+        GameVariant::ShortDeck,
+        &[cards("Ac Ad"), cards("9c 9d")],
+        &cards("Kh 7s 6c Js"),
+        DEFAULT_SAMPLES,
+        1,
+    );
+    assert!((shares[1] - 2.0 / 28.0).abs() < 1e-9, "{shares:?}");
+}
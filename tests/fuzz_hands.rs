@@ -0,0 +1,492 @@
+//! Random but rules-valid hands played batch by batch through a `TableSession`,
+//! with the table invariants checked after every `app.update()`.
+//! `FUZZ_SEEDS=<count>` runs more seeds, `FUZZ_SEED=<seed>` replays a single one.
+
+use std::collections::HashSet;
+use std::panic;
+use std::sync::{Mutex, PoisonError};
+
+use ecs_example::*;
+
+const DEFAULT_SEEDS: u64 = 100;
+const HANDS_PER_SEED: usize = 6;
+const SMALL_BLIND: u64 = 50;
+const BIG_BLIND: u64 = 100;
+const MAX_SEATS: u8 = 6;
+
+/// Seed and batch being applied, printed when a system panics: the executor
+/// aborts the process, so the panic can't be caught and reported as a failure.
+static FUZZ_CASE: Mutex<String> = Mutex::new(String::new());
+
+/// xorshift64*, enough to be reproducible without extra dependencies.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Rng(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+
+    fn range(&mut self, low: u64, high: u64) -> u64 {
+        low + self.below(high - low + 1)
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        (1..items.len()).rev().for_each(|index| {
+            let other_index = self.below(index as u64 + 1) as usize;
+            items.swap(index, other_index);
+        });
+    }
+}
+
+struct Seat {
+    name: String,
+    seat_num: u8,
+    stack: u64,
+    hand_bets: u64,
+    street_bet: u64,
+    folded: bool,
+    acted: bool,
+}
+
+impl Seat {
+    fn can_act(&self) -> bool {
+        !self.folded && self.stack > 0
+    }
+}
+
+/// What the generator knows after a batch, to compare with the tracker.
+struct Expected {
+    /// Not known to the tracker until the stacks of the hand are sent.
+    chips: Option<u64>,
+    to_act: Option<String>,
+}
+
+struct HandGenerator {
+    rng: Rng,
+    seats: Vec<Seat>,
+    hero: String,
+    dealer_index: usize,
+    hand_num: u64,
+    /// A smaller all-in raise doesn't reopen the betting.
+    last_raise_size: u64,
+}
+
+impl HandGenerator {
+    fn new(seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+        let mut seat_nums = (1..=MAX_SEATS).collect::<Vec<_>>();
+        rng.shuffle(&mut seat_nums);
+        let players = rng.range(2, u64::from(MAX_SEATS)) as usize;
+        let mut seat_nums = seat_nums[..players].to_vec();
+        seat_nums.sort();
+
+        let seats = seat_nums
+            .iter()
+            .map(|&seat_num| Seat {
+                name: format!("player{seat_num}"),
+                seat_num,
+                stack: 0,
+                hand_bets: 0,
+                street_bet: 0,
+                folded: false,
+                acted: false,
+            })
+            .collect::<Vec<_>>();
+        let hero = seats[rng.below(players as u64) as usize].name.clone();
+        let dealer_index = rng.below(players as u64) as usize;
+
+        HandGenerator {
+            rng,
+            seats,
+            hero,
+            dealer_index,
+            hand_num: 0,
+            last_raise_size: BIG_BLIND,
+        }
+    }
+
+    fn seat_actions(&self) -> Vec<Action> {
+        self.seats
+            .iter()
+            .map(|seat| {
+                Action::SeatUpdated(SeatUpdatedParams {
+                    name: seat.name.clone(),
+                    seat_num: seat.seat_num,
+                    npc: seat.name == self.hero,
+                })
+            })
+            .collect()
+    }
+
+    fn expected(&self, to_act: Option<usize>) -> Expected {
+        Expected {
+            chips: Some(
+                self.seats
+                    .iter()
+                    .map(|seat| seat.stack + seat.hand_bets)
+                    .sum(),
+            ),
+            to_act: to_act.map(|index| self.seats[index].name.clone()),
+        }
+    }
+
+    fn next_index(&self, index: usize) -> usize {
+        (index + 1) % self.seats.len()
+    }
+
+    /// First player after `index` who has to put chips in or check,
+    /// `None` when the betting of the street is over.
+    fn next_to_act(&self, index: usize) -> Option<usize> {
+        if self.in_hand() < 2 {
+            return None;
+        }
+
+        let street_max_bet = self.street_max_bet();
+        let can_act = self.seats.iter().filter(|seat| seat.can_act()).count();
+        let mut next_index = index;
+        for _ in 0..self.seats.len() {
+            next_index = self.next_index(next_index);
+            let seat = &self.seats[next_index];
+            if seat.can_act() && (seat.street_bet < street_max_bet || (can_act > 1 && !seat.acted))
+            {
+                return Some(next_index);
+            }
+        }
+
+        None
+    }
+
+    fn street_max_bet(&self) -> u64 {
+        self.seats
+            .iter()
+            .map(|seat| seat.street_bet)
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn bet(&mut self, index: usize, bet_size: u64) -> Action {
+        let seat = &mut self.seats[index];
+        seat.stack -= bet_size;
+        seat.hand_bets += bet_size;
+        seat.street_bet += bet_size;
+
+        Action::BetMade(BetMadeParams {
+            seat_index: seat.seat_num,
+            bet_size,
+        })
+    }
+
+    /// Fold, check, call, bet, raise or all-in, never more than the stack.
+    /// Players who acted before a short all-in raise may only call or fold.
+    fn random_bet(&mut self, index: usize) -> Action {
+        let street_max_bet = self.street_max_bet();
+        let Seat {
+            stack,
+            street_bet,
+            acted,
+            ..
+        } = self.seats[index];
+        let to_call = street_max_bet - street_bet;
+
+        let bet_size = match self.rng.below(100) {
+            0..=19 if to_call > 0 => {
+                self.seats[index].folded = true;
+                0
+            }
+            _ if acted => to_call.min(stack),
+            0..=59 => to_call.min(stack),
+            60..=89 => {
+                let raise_to = street_max_bet.max(BIG_BLIND) * self.rng.range(2, 4);
+                (raise_to - street_bet).min(stack)
+            }
+            _ => stack,
+        };
+
+        let raise_size = (street_bet + bet_size).saturating_sub(street_max_bet);
+        if raise_size > 0 && raise_size >= self.last_raise_size {
+            self.last_raise_size = raise_size;
+            self.seats.iter_mut().for_each(|seat| seat.acted = false);
+        }
+        self.seats[index].acted = true;
+
+        self.bet(index, bet_size)
+    }
+
+    fn in_hand(&self) -> usize {
+        self.seats.iter().filter(|seat| !seat.folded).count()
+    }
+
+    /// One hand as batches, each with what the tracker must show after it.
+    fn hand(&mut self) -> Vec<(Vec<Action>, Expected)> {
+        let mut batches = vec![];
+        self.hand_num += 1;
+        self.dealer_index = self.next_index(self.dealer_index);
+        for seat in &mut self.seats {
+            if seat.stack == 0 {
+                seat.stack = self.rng.range(BIG_BLIND, 200 * BIG_BLIND);
+            }
+            seat.hand_bets = 0;
+            seat.street_bet = 0;
+            seat.folded = false;
+            seat.acted = false;
+        }
+        self.last_raise_size = BIG_BLIND;
+
+        batches.push((
+            vec![
+                Action::GameHandIdSet(format!("{}", 1000 + self.hand_num)),
+                Action::GameTypeSet(GameType::NL),
+                Action::GameLimitSet(GameLimit::new(SMALL_BLIND, BIG_BLIND, Currency::Chips)),
+            ],
+            Expected {
+                chips: None,
+                to_act: None,
+            },
+        ));
+        batches.push((
+            vec![
+                Action::GameMaxSeatsSet(MAX_SEATS),
+                Action::GameDealerSeatNumSet(self.seats[self.dealer_index].seat_num),
+            ],
+            Expected {
+                chips: None,
+                to_act: None,
+            },
+        ));
+        batches.push((
+            self.seats
+                .iter()
+                .map(|seat| {
+                    Action::StackUpdated(StackUpdatedParams {
+                        name: seat.name.clone(),
+                        stack: seat.stack,
+                    })
+                })
+                .collect(),
+            self.expected(None),
+        ));
+
+        let mut deck = Card::ALL.to_vec();
+        self.rng.shuffle(&mut deck);
+
+        // Heads-up the button posts the small blind
+        let small_blind_index = if self.seats.len() == 2 {
+            self.dealer_index
+        } else {
+            self.next_index(self.dealer_index)
+        };
+        let big_blind_index = self.next_index(small_blind_index);
+        for (index, blind) in [
+            (small_blind_index, SMALL_BLIND),
+            (big_blind_index, BIG_BLIND),
+        ] {
+            let bet_size = blind.min(self.seats[index].stack);
+            let action = self.bet(index, bet_size);
+            batches.push((vec![action], self.expected(self.next_to_act(index))));
+        }
+
+        let hero_cards = vec![deck.pop().unwrap(), deck.pop().unwrap()];
+        batches.push((
+            vec![Action::NpcCardsDealt(NpcCardsDealtParams {
+                name: self.hero.clone(),
+                cards: hero_cards,
+            })],
+            self.expected(self.next_to_act(big_blind_index)),
+        ));
+
+        let mut board = vec![];
+        let mut last_index = big_blind_index;
+        for street_cards in [0, 3, 1, 1] {
+            if street_cards > 0 {
+                if self.in_hand() < 2 {
+                    break;
+                }
+                let new_cards = deck.split_off(deck.len() - street_cards);
+                self.seats.iter_mut().for_each(|seat| {
+                    seat.street_bet = 0;
+                    seat.acted = false;
+                });
+                self.last_raise_size = BIG_BLIND;
+                last_index = self.dealer_index;
+                batches.push((
+                    vec![Action::CommunityCardsDealt(CommunityCardsDealtParams {
+                        prev_cards: board.clone(),
+                        new_cards: new_cards.clone(),
+                    })],
+                    self.expected(self.next_to_act(last_index)),
+                ));
+                board.extend(new_cards);
+            }
+
+            while let Some(index) = self.next_to_act(last_index) {
+                let action = self.random_bet(index);
+                last_index = index;
+                batches.push((vec![action], self.expected(self.next_to_act(index))));
+            }
+        }
+
+        // Showdown, the pot goes to a random player still in the hand
+        let pot = self.seats.iter().map(|seat| seat.hand_bets).sum::<u64>();
+        let winners = (0..self.seats.len())
+            .filter(|&index| !self.seats[index].folded)
+            .collect::<Vec<_>>();
+        let winner = winners[self.rng.below(winners.len() as u64) as usize];
+        self.seats[winner].stack += pot;
+        self.seats.iter_mut().for_each(|seat| seat.hand_bets = 0);
+
+        batches
+    }
+}
+
+/// Every broken invariant of the table after one batch.
+fn check_invariants(table_view: &TableView, expected: &Expected, hero: &str) -> Vec<String> {
+    let mut violations = vec![];
+
+    let chips = table_view
+        .players
+        .iter()
+        .map(|player| player.stack.unwrap_or_default())
+        .sum::<u64>()
+        + table_view.pot;
+    if let Some(expected_chips) = expected
+        .chips
+        .filter(|expected_chips| *expected_chips != chips)
+    {
+        violations.push(format!(
+            "chips not conserved: stacks and pot hold {chips}, expected {expected_chips}"
+        ));
+    }
+
+    let mut known_cards = HashSet::new();
+    table_view
+        .board
+        .iter()
+        .chain(table_view.players.iter().flat_map(|player| &player.cards))
+        .filter(|card| !known_cards.insert(**card))
+        .for_each(|card| violations.push(format!("card {card} is dealt twice")));
+
+    let need_decision = table_view
+        .players
+        .iter()
+        .filter(|player| player.need_decision)
+        .map(|player| player.name.as_str())
+        .collect::<Vec<_>>();
+    if need_decision.len() > 1 {
+        violations.push(format!("several players need decision: {need_decision:?}"));
+    }
+    if let Some(name) = need_decision
+        .iter()
+        .find(|name| Some(**name) != expected.to_act.as_deref())
+    {
+        violations.push(format!(
+            "{name} needs decision, but {:?} is to act",
+            expected.to_act
+        ));
+    }
+    if need_decision.iter().any(|name| *name != hero) {
+        violations.push(format!("not the hero needs decision: {need_decision:?}"));
+    }
+    if expected.to_act.as_deref() == Some(hero) && need_decision != [hero] {
+        violations.push(format!(
+            "{hero} is to act, but needs decision: {need_decision:?}"
+        ));
+    }
+
+    violations
+}
+
+fn run_seed(seed: u64) -> Result<(), String> {
+    let mut hand_generator = HandGenerator::new(seed);
+    let mut table_session = TableSession::with_plugin(PokerTrackerPlugin {
+        display: DisplayMode::Off,
+        chip_conservation: true,
+        ..Default::default()
+    });
+
+    let seat_actions = hand_generator.seat_actions();
+    let hero = hand_generator.hero.clone();
+    table_session
+        .apply(seat_actions)
+        .map_err(|err| format!("seating failed - {err}"))?;
+
+    for hand_num in 1..=HANDS_PER_SEED {
+        for (batch_num, (actions, expected)) in hand_generator.hand().into_iter().enumerate() {
+            *FUZZ_CASE.lock().unwrap_or_else(PoisonError::into_inner) =
+                format!("seed {seed}: hand {hand_num} batch {batch_num} {actions:?}");
+            let mut violations = match table_session.apply(actions.clone()) {
+                Ok(outcome) => outcome
+                    .warnings
+                    .iter()
+                    .map(|warning| format!("tracker warning - {warning}"))
+                    .collect(),
+                Err(err) => vec![format!("tracker error - {err}")],
+            };
+            violations.extend(check_invariants(
+                &table_session.table_view(),
+                &expected,
+                &hero,
+            ));
+
+            if !violations.is_empty() {
+                return Err(format!(
+                    "hand {hand_num} batch {batch_num} {actions:?}:\n  {}",
+                    violations.join("\n  ")
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn random_hands_keep_invariants() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |panic_info| {
+        eprintln!(
+            "fuzz case {}",
+            FUZZ_CASE.lock().unwrap_or_else(PoisonError::into_inner)
+        );
+        default_hook(panic_info);
+    }));
+
+    let seeds = match std::env::var("FUZZ_SEED") {
+        Ok(seed) => {
+            let seed = seed.parse::<u64>().expect("FUZZ_SEED should be a number");
+            seed..seed + 1
+        }
+        Err(_) => {
+            let seeds = std::env::var("FUZZ_SEEDS")
+                .ok()
+                .and_then(|seeds| seeds.parse().ok())
+                .unwrap_or(DEFAULT_SEEDS);
+            0..seeds
+        }
+    };
+
+    let failures = seeds
+        .filter_map(|seed| {
+            run_seed(seed)
+                .err()
+                .map(|err| format!("seed {seed}: {err}"))
+        })
+        .collect::<Vec<_>>();
+
+    assert!(
+        failures.is_empty(),
+        "{} seeds failed, first:\n{}",
+        failures.len(),
+        failures[0]
+    );
+}
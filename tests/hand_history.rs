@@ -0,0 +1,154 @@
+use ecs_example::hand_history_parser::HandHistoryParser;
+use ecs_example::*;
+
+mod common;
+
+use common::{bet, hand_history, pot_awarded, seat, stack};
+
+fn replay(batches: &[Vec<Action>]) -> TableSession {
+    let mut session = TableSession::new();
+    for batch in batches {
+        if let Err(tracker_errors) = session.apply(batch.clone()) {
+            panic!("{batch:?} was rejected: {tracker_errors}");
+        }
+    }
+
+    session
+}
+
+/// Exported text parses back to the same actions and the same table.
+fn assert_round_trip(file_name: &str) {
+    let batches = HandHistoryParser::default()
+        .parse_hand(&hand_history(file_name))
+        .unwrap_or_else(|err| panic!("Can't parse {file_name}: {err}"));
+    let session = replay(&batches);
+
+    let exported = session.hand_history().expect("No hand to export");
+    let exported_batches = HandHistoryParser::default()
+        .parse_hand(&exported)
+        .unwrap_or_else(|err| panic!("Can't parse exported {file_name}: {err}\n{exported}"));
+    assert_eq!(exported_batches, batches, "\n{exported}");
+    assert_eq!(
+        replay(&exported_batches).table_view(),
+        session.table_view(),
+        "\n{exported}"
+    );
+}
+
+#[test]
+fn simple_hand_round_trips() {
+    assert_round_trip("simple_cash.txt");
+}
+
+#[test]
+fn ante_hand_round_trips() {
+    assert_round_trip("ante_tournament.txt");
+}
+
+#[test]
+fn side_pot_hand_round_trips() {
+    assert_round_trip("side_pot.txt");
+}
+
+#[test]
+fn journal_keeps_two_hands_with_tracked_buttons() {
+    let seats = vec![seat("Ann", 1), seat("Bob", 2), seat("Carol", 3)];
+    let stacks = vec![stack("Ann", 1000), stack("Bob", 1000), stack("Carol", 1000)];
+    let game = |hand_id: &str| {
+        vec![
+            Action::GameHandIdSet(hand_id.into()),
+            Action::GameTypeSet(GameType::NL),
+            Action::GameLimitSet(GameLimit::new(5, 10, Currency::Chips)),
+            Action::GameTableNameSet("Aase III".into()),
+        ]
+    };
+    // The button is only told for the first hand, the blinds move it on
+    let batches = [
+        vec![seats],
+        vec![game("1"), vec![Action::GameDealerSeatNumSet(1)], stacks],
+        vec![
+            vec![bet(2, 5)],
+            vec![bet(3, 10)],
+            vec![bet(1, 0)],
+            vec![bet(2, 0)],
+        ],
+        vec![vec![pot_awarded("Carol", 10)]],
+        vec![game("2")],
+        vec![
+            vec![bet(3, 5)],
+            vec![bet(1, 10)],
+            vec![bet(2, 0)],
+            vec![bet(3, 0)],
+        ],
+        vec![vec![pot_awarded("Ann", 10)]],
+        vec![game("3")],
+        vec![
+            vec![bet(1, 5)],
+            vec![bet(2, 10)],
+            vec![bet(3, 0)],
+            vec![bet(1, 0)],
+        ],
+        vec![vec![pot_awarded("Bob", 10)]],
+    ]
+    .concat();
+    let session = replay(&batches);
+    let journal = session.journal();
+
+    let hand_ids = journal
+        .actions()
+        .iter()
+        .filter_map(|action| match action {
+            Action::GameHandIdSet(hand_id) => Some(hand_id.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(hand_ids, vec!["2", "3"]);
+    assert_eq!(journal.hand_history("1"), None);
+
+    let previous_hand = journal.hand_history("2").expect("No previous hand");
+    assert!(
+        previous_hand.contains("Table 'Aase III' 3-max Seat #2 is the button"),
+        "{previous_hand}"
+    );
+    assert!(
+        previous_hand.contains("Seat 3: Carol (1005 in chips)"),
+        "{previous_hand}"
+    );
+    let last_hand = session.hand_history().expect("No last hand");
+    assert!(
+        last_hand.contains("Table 'Aase III' 3-max Seat #3 is the button"),
+        "{last_hand}"
+    );
+    assert!(
+        last_hand.contains("Seat 1: Ann (1005 in chips)"),
+        "{last_hand}"
+    );
+}
+
+#[test]
+fn amounts_have_the_decimals_of_the_denomination() {
+    let game_limit = GameLimit::new(5, 10, Currency::USD);
+    assert_eq!(game_limit.format_amount(50), "$0.50");
+    assert_eq!(game_limit.format_amount(1234), "$12.34");
+
+    let game_limit = GameLimit {
+        denomination: 1000,
+        ..game_limit
+    };
+    assert_eq!(game_limit.decimals(), Some(3));
+    assert_eq!(game_limit.format_amount(1234), "$1.234");
+    assert_eq!(game_limit.format_amount(5), "$0.005");
+
+    assert_eq!(
+        GameLimit::new(50, 100, Currency::Chips).format_amount(1500),
+        "1500"
+    );
+    assert_eq!(
+        GameLimit {
+            denomination: 20,
+            ..game_limit
+        }
+        .decimals(),
+        None
+    );
+}
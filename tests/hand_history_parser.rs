@@ -0,0 +1,208 @@
+use ecs_example::hand_history_parser::{take_complete_hands, HandHistoryParser};
+use ecs_example::session::TableSession;
+use ecs_example::*;
+
+mod common;
+
+use common::hand_history;
+
+fn player<'a>(table_view: &'a TableView, name: &str) -> &'a PlayerView {
+    table_view
+        .players
+        .iter()
+        .find(|player| player.name == name)
+        .unwrap_or_else(|| panic!("{name} is not seated"))
+}
+
+#[test]
+fn antes_are_posted_and_deducted() {
+    let batches = HandHistoryParser::default()
+        .parse_hand(&hand_history("ante_tournament.txt"))
+        .expect("Can't parse ante hand");
+
+    let antes = batches
+        .iter()
+        .flatten()
+        .filter_map(|action| match action {
+            Action::AntePosted(AntePostedParams { seat_index, ante }) => Some((*seat_index, *ante)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(antes, vec![(1, 10), (2, 10), (4, 10), (6, 10)]);
+
+    let mut session = TableSession::new();
+    let mut batches = batches.into_iter();
+    for batch in batches.by_ref() {
+        let is_last_ante = batch
+            == vec![Action::AntePosted(AntePostedParams {
+                seat_index: 6,
+                ante: 10,
+            })];
+        session.apply(batch).expect("Ante hand was rejected");
+        if is_last_ante {
+            break;
+        }
+    }
+    let table_view = session.table_view();
+    assert_eq!(table_view.pot, 40);
+    assert_eq!(table_view.round_max_bet, 0);
+    assert_eq!(player(&table_view, "Ann").stack, Some(2860));
+    assert_eq!(player(&table_view, "Ann").ante, 10);
+
+    for batch in batches {
+        session.apply(batch).expect("Ante hand was rejected");
+    }
+    let table_view = session.table_view();
+    assert_eq!(table_view.rake.map(|rake| rake.total()), Some(0));
+    let stacks = ["Ann", "Bob", "Carol", "Hero"].map(|name| player(&table_view, name).stack);
+    assert_eq!(stacks, [Some(2760), Some(1940), Some(2890), Some(3010)]);
+}
+
+#[test]
+fn hands_are_split_once_their_summary_is_finished() {
+    let simple_cash = hand_history("simple_cash.txt");
+    let side_pot = hand_history("side_pot.txt");
+    let unfinished = &side_pot[..side_pot.find("*** SUMMARY ***").unwrap()];
+    let mut buffer = format!("{simple_cash}{unfinished}");
+
+    assert_eq!(take_complete_hands(&mut buffer).len(), 1);
+    assert_eq!(buffer.trim(), unfinished.trim());
+
+    buffer.push_str(&side_pot[unfinished.len()..]);
+    assert_eq!(take_complete_hands(&mut buffer).len(), 1);
+    assert!(buffer.trim().is_empty(), "{buffer:?}");
+}
+
+#[test]
+fn table_changes_between_hands_are_parsed() {
+    let mut parser = HandHistoryParser::default();
+    let first_hand = parser
+        .parse_hand(&hand_history("simple_cash.txt"))
+        .expect("Can't parse first hand");
+    assert_eq!(
+        first_hand[1],
+        vec![
+            Action::GameHandIdSet("243571110001".into()),
+            Action::GameTypeSet(GameType::NL),
+            Action::GameVariantSet(GameVariant::Holdem),
+            Action::GameLimitSet(GameLimit::new(5, 10, Currency::USD)),
+        ]
+    );
+    assert_eq!(
+        first_hand[2],
+        vec![
+            Action::GameMaxSeatsSet(6),
+            Action::GameTableNameSet("Aase III".into()),
+            Action::GameDealerSeatNumSet(2),
+        ]
+    );
+    assert!(
+        first_hand.contains(&vec![Action::NpcCardsDealt(NpcCardsDealtParams {
+            name: "Hero".into(),
+            cards: vec![Card::SQ, Card::DQ],
+        })])
+    );
+    assert_eq!(
+        first_hand.last(),
+        Some(&vec![Action::PotAwarded(PotAwardedParams {
+            pots: vec![PotShare {
+                name: "Hero".into(),
+                amount: 431,
+            }],
+        })])
+    );
+
+    // Dan is gone and nobody new sat down, the other seats are kept
+    let second_hand = parser
+        .parse_hand(&hand_history("side_pot.txt"))
+        .expect("Can't parse second hand");
+    assert_eq!(
+        second_hand[0],
+        vec![Action::PlayerLeft(PlayerParams { name: "Dan".into() })]
+    );
+    assert!(matches!(second_hand[1][0], Action::GameHandIdSet(_)));
+    assert_eq!(
+        second_hand.last(),
+        Some(&vec![Action::PotAwarded(PotAwardedParams {
+            pots: vec![
+                PotShare {
+                    name: "Hero".into(),
+                    amount: 600,
+                },
+                PotShare {
+                    name: "Hero".into(),
+                    amount: 570,
+                },
+            ],
+        })])
+    );
+}
+
+#[test]
+fn tournament_header_starts_the_tournament_once() {
+    let mut parser = HandHistoryParser::default();
+    let batches = parser
+        .parse_hand(&hand_history("ante_tournament.txt"))
+        .expect("Can't parse tournament hand");
+    let tournament_ids = |batches: &[Vec<Action>]| {
+        batches
+            .iter()
+            .flatten()
+            .filter_map(|action| match action {
+                Action::TournamentStarted(TournamentStartedParams { tournament_id, .. }) => {
+                    Some(tournament_id.clone())
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(tournament_ids(&batches), vec!["2964581230"]);
+
+    let mut session = TableSession::new();
+    for batch in batches {
+        session.apply(batch).expect("Tournament hand was rejected");
+    }
+    let tournament = session.table_view().tournament.expect("No tournament");
+    assert_eq!(tournament.tournament_id, "2964581230");
+    assert_eq!(tournament.hands, 1);
+
+    // Later hands of the same tournament go on with it
+    let batches = parser
+        .parse_hand(&hand_history("ante_tournament.txt"))
+        .expect("Can't parse tournament hand");
+    assert_eq!(tournament_ids(&batches), Vec::<String>::new());
+}
+
+#[test]
+fn thousands_separators_are_stripped_from_amounts() {
+    let stacks = |hand_text: &str| {
+        HandHistoryParser::default()
+            .parse_hand(hand_text)
+            .expect("Can't parse hand")
+            .into_iter()
+            .flatten()
+            .filter_map(|action| match action {
+                Action::StackUpdated(StackUpdatedParams { name, stack }) => Some((name, stack)),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let cash_stacks = stacks(
+        &hand_history("simple_cash.txt")
+            .replace("Bob ($12.35 in chips)", "Bob ($1,234.56 in chips)"),
+    );
+    assert!(
+        cash_stacks.contains(&("Bob".into(), 123456)),
+        "{cash_stacks:?}"
+    );
+
+    let chip_stacks = stacks(
+        &hand_history("ante_tournament.txt")
+            .replace("Carol (3300 in chips)", "Carol (3,300 in chips)"),
+    );
+    assert!(
+        chip_stacks.contains(&("Carol".into(), 3300)),
+        "{chip_stacks:?}"
+    );
+}
@@ -0,0 +1,91 @@
+use ecs_example::hand_history_parser::HandHistoryParser;
+use ecs_example::server::{ServerReply, ServerRequest, TrackerServer};
+use ecs_example::stats::PlayerStats;
+use serde_json::Value;
+
+/// Server tracking `simple_cash.txt` on each of `tables`.
+mod common;
+
+use common::hand_history;
+fn simple_cash_server(tables: &[&str]) -> TrackerServer {
+    let batches = HandHistoryParser::default()
+        .parse_hand(&hand_history("simple_cash.txt"))
+        .expect("Can't parse simple_cash.txt");
+
+    let mut tracker_server = TrackerServer::default();
+    for table in tables {
+        for batch in &batches {
+            let reply = tracker_server.handle(ServerRequest {
+                table: table.to_string(),
+                actions: batch.clone(),
+            });
+            assert!(matches!(reply, ServerReply::Ok { .. }), "{reply:?}");
+        }
+    }
+
+    tracker_server
+}
+
+fn query_json(tracker_server: &TrackerServer, path: &str) -> Value {
+    let query_reply = tracker_server.query(path);
+    assert_eq!(query_reply.status, 200, "{path}: {}", query_reply.body);
+    serde_json::from_str(&query_reply.body)
+        .unwrap_or_else(|err| panic!("{path}: {err}\n{}", query_reply.body))
+}
+
+#[test]
+fn tables_and_hands_are_found_by_decoded_path() {
+    let tracker_server = simple_cash_server(&["Aase III"]);
+
+    let tables = query_json(&tracker_server, "/tables");
+    assert_eq!(
+        tables,
+        serde_json::json!([{
+            "table": "Aase III",
+            "hand_id": "243571110001",
+            "street": "River",
+            "pot": 0,
+            "players": 4,
+        }])
+    );
+
+    let table = query_json(&tracker_server, "/tables/Aase%20III");
+    assert_eq!(table["table"], "Aase III");
+    assert_eq!(table["hand_id"], "243571110001");
+    assert_eq!(table["players"].as_array().map(Vec::len), Some(4));
+
+    let hand = query_json(&tracker_server, "/hands/243571110001");
+    assert_eq!(hand["table"], "Aase III");
+    assert!(
+        hand["text"]
+            .as_str()
+            .is_some_and(|text| text.starts_with("PokerStars Hand #243571110001")),
+        "{hand}"
+    );
+
+    for path in ["/tables/Aase", "/hands/1", "/players", "/"] {
+        assert_eq!(tracker_server.query(path).status, 404, "{path}");
+    }
+}
+
+#[test]
+fn player_stats_add_up_across_tables() {
+    let tracker_server = simple_cash_server(&["Aase III", "Aase IV"]);
+
+    let player_stats =
+        serde_json::from_value::<PlayerStats>(query_json(&tracker_server, "/players/Hero/stats"))
+            .expect("Can't read player stats");
+    assert_eq!(
+        player_stats,
+        PlayerStats {
+            name: "Hero".into(),
+            hands: 2,
+            vpip_hands: 2,
+            preflop_raise_hands: 2,
+            total_bet: 940,
+            total_bet_big_blinds: 94.0,
+        }
+    );
+
+    assert_eq!(tracker_server.query("/players/Eve/stats").status, 404);
+}
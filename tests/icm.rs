@@ -0,0 +1,120 @@
+use ecs_example::decision::{DecisionMode, PushFold};
+use ecs_example::icm::{all_in_delta, icm_equities};
+use ecs_example::view::TournamentView;
+use ecs_example::*;
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+}
+
+#[test]
+fn icm_equities_of_known_spots() {
+    // Heads-up the second prize is locked up, the rest follows the chips
+    let equities = icm_equities(&[3000, 1000], &[100, 50]);
+    assert_close(equities[0], 87.5);
+    assert_close(equities[1], 62.5);
+
+    let equities = icm_equities(&[2000, 2000, 2000], &[50, 30, 20]);
+    equities
+        .iter()
+        .for_each(|equity| assert_close(*equity, 100.0 / 3.0));
+
+    // The chip leader is worth less than their share of chips, busted players nothing
+    let equities = icm_equities(&[7000, 2000, 1000, 0], &[50, 30, 20]);
+    assert_close(equities.iter().sum(), 100.0);
+    assert!(equities[0] < 70.0 && equities[2] > 10.0, "{equities:?}");
+    assert_close(equities[3], 0.0);
+}
+
+#[test]
+fn bubble_all_in_is_chip_ev_but_not_dollar_ev() {
+    let stacks = [5000, 5000, 5000, 500];
+    let bets = [0, 0, 0, 0];
+    let delta = all_in_delta(&stacks, &bets, &[50, 30, 20], 0, 1, 0.55);
+
+    assert_close(delta.chip_ev, 500.0);
+    assert!(delta.dollar_ev < 0.0, "{delta:?}");
+}
+
+#[test]
+fn decision_request_surfaces_ev_delta() {
+    let player = |name: &str, seat_num: u8, stack: u64, round_bets: Vec<u64>| PlayerView {
+        name: name.into(),
+        seat_num,
+        status: PlayerStatus::Active,
+        stack: Some(stack),
+        round_bets,
+        ..Default::default()
+    };
+    let mut table_view = TableView {
+        round_max_bet: 200,
+        players: vec![
+            PlayerView {
+                cards: vec![Card::HA, Card::SA],
+                need_decision: true,
+                npc: true,
+                ..player("Hero", 1, 5000, vec![])
+            },
+            player("Bob", 2, 4900, vec![100]),
+            player("Carol", 3, 4800, vec![200]),
+            player("Dave", 4, 500, vec![]),
+        ],
+        ..Default::default()
+    };
+    assert_eq!(
+        DecisionRequest::from_view(&table_view).and_then(|request| request.ev_delta),
+        None
+    );
+
+    table_view.tournament = Some(TournamentView {
+        payouts: vec![50, 30, 20],
+        ..Default::default()
+    });
+    let decision_request = DecisionRequest::from_view(&table_view).expect("Hero has to act");
+    let ev_delta = decision_request.ev_delta.expect("Payouts are known");
+    assert!(ev_delta.chip_ev > ev_delta.dollar_ev, "{ev_delta:?}");
+    assert_eq!(
+        decision_request.push_fold(DecisionMode::Icm),
+        Some(PushFold::Push)
+    );
+}
+
+#[test]
+fn antes_are_dead_money_of_the_all_in() {
+    let player = |name: &str, seat_num: u8, stack: u64, ante: u64| PlayerView {
+        name: name.into(),
+        seat_num,
+        status: PlayerStatus::Active,
+        stack: Some(stack),
+        ante,
+        ..Default::default()
+    };
+    let table_view = |ante: u64| TableView {
+        players: vec![
+            PlayerView {
+                cards: vec![Card::HK, Card::SK],
+                need_decision: true,
+                npc: true,
+                ..player("Hero", 1, 3000 - ante, ante)
+            },
+            player("Bob", 2, 5000 - ante, ante),
+            player("Carol", 3, 2000 - ante, ante),
+            player("Dave", 4, 1000 - ante, ante),
+        ],
+        tournament: Some(TournamentView {
+            payouts: vec![50, 30, 20],
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let chip_ev = |ante: u64| {
+        DecisionRequest::from_view(&table_view(ante))
+            .and_then(|request| request.ev_delta)
+            .expect("Payouts are known")
+            .chip_ev
+    };
+
+    // Folding gives up the own ante, winning takes the antes of Carol and Dave as well
+    let ante_gain = chip_ev(100) - chip_ev(0);
+    assert!(ante_gain > 100.0 && ante_gain < 300.0, "{ante_gain}");
+}
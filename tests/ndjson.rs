@@ -0,0 +1,87 @@
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+use bevy::ecs::event::Events;
+use bevy::prelude::*;
+use ecs_example::hand_history_parser::HandHistoryParser;
+use ecs_example::ndjson::{
+    write_record, ActionRecord, ActionRecorder, NdjsonError, NdjsonReader, ACTION_SCHEMA_VERSION,
+};
+use ecs_example::*;
+
+mod common;
+
+use common::hand_history;
+
+/// Recorder output the test can read while the App still owns the writer.
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn recorded_stream_reads_back_as_the_same_batches() {
+    let mut parser = HandHistoryParser::default();
+    let batches = ["simple_cash.txt", "side_pot.txt"]
+        .iter()
+        .flat_map(|file_name| {
+            parser
+                .parse_hand(&hand_history(file_name))
+                .unwrap_or_else(|err| panic!("Can't parse {file_name}: {err}"))
+        })
+        .collect::<Vec<_>>();
+
+    let record_buffer = SharedBuffer::default();
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(PokerTrackerPlugin {
+            display: DisplayMode::Off,
+            ..default()
+        })
+        .insert_resource(ActionRecorder::new(record_buffer.clone()));
+    for batch in &batches {
+        let mut event_source = app.world.resource_mut::<Events<Action>>();
+        batch
+            .iter()
+            .for_each(|action| event_source.send(action.clone()));
+        app.update();
+    }
+
+    let recorded = record_buffer.0.lock().unwrap().clone();
+    let read_batches = NdjsonReader::new(recorded.as_slice())
+        .collect::<Result<Vec<_>, _>>()
+        .expect("Can't read recorded stream");
+    assert_eq!(read_batches, batches);
+}
+
+#[test]
+fn other_schema_version_is_rejected() {
+    let mut stream = vec![];
+    for (version, batch) in [(ACTION_SCHEMA_VERSION, 0), (ACTION_SCHEMA_VERSION + 1, 1)] {
+        let record = ActionRecord {
+            version,
+            batch,
+            action: Action::GameHandIdSet(batch.to_string()),
+        };
+        write_record(&mut stream, &record).expect("Can't write record");
+    }
+
+    let mut ndjson_reader = NdjsonReader::new(stream.as_slice());
+    let first_batch = ndjson_reader.next();
+    assert!(
+        matches!(
+            first_batch,
+            Some(Err(NdjsonError::UnsupportedVersion { line: 2, version }))
+                if version == ACTION_SCHEMA_VERSION + 1
+        ),
+        "{first_batch:?}"
+    );
+}
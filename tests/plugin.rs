@@ -0,0 +1,73 @@
+use bevy::ecs::event::Events;
+use bevy::prelude::*;
+use ecs_example::conservation::ChipConservation;
+use ecs_example::decision::DecisionMode;
+use ecs_example::error::TrackerErrors;
+use ecs_example::*;
+
+mod common;
+
+use common::{seat, stack};
+
+fn tracker_app(plugin: PokerTrackerPlugin) -> App {
+    let mut app = App::new();
+    app.add_plugin(plugin);
+    app
+}
+
+fn apply(app: &mut App, actions: Vec<Action>) -> Vec<TrackerError> {
+    let mut event_source = app.world.resource_mut::<Events<Action>>();
+    actions
+        .into_iter()
+        .for_each(|action| event_source.send(action));
+    app.update();
+
+    std::mem::take(&mut app.world.resource_mut::<TrackerErrors>().0)
+}
+
+#[test]
+fn tracker_runs_in_a_library_app() {
+    let mut app = tracker_app(PokerTrackerPlugin {
+        display: DisplayMode::Off,
+        ..default()
+    });
+    let errors = apply(&mut app, vec![seat("Ann", 1), stack("Ann", 1000)]);
+
+    assert_eq!(errors, vec![]);
+    let table_view = app.world.resource::<TableView>();
+    assert_eq!(table_view.players.len(), 1);
+    assert_eq!(table_view.players[0].stack, Some(1000));
+    assert_eq!(app.world.get_resource::<DecisionMode>(), Some(&default()));
+    assert!(app.world.get_resource::<ChipConservation>().is_none());
+}
+
+#[test]
+fn parts_of_the_tracker_are_switched_off() {
+    let three_hole_cards = || {
+        vec![Action::NpcCardsDealt(NpcCardsDealtParams {
+            name: "Hero".into(),
+            cards: vec![Card::HA, Card::SA, Card::DA],
+        })]
+    };
+    let is_wrong_hole_cards =
+        |err: &TrackerError| matches!(err, TrackerError::WrongHoleCards { .. });
+
+    let mut app = tracker_app(PokerTrackerPlugin {
+        display: DisplayMode::Off,
+        ..default()
+    });
+    let errors = apply(&mut app, three_hole_cards());
+    assert!(errors.iter().any(is_wrong_hole_cards), "{errors:?}");
+
+    let mut app = tracker_app(PokerTrackerPlugin {
+        validation: false,
+        display: DisplayMode::Off,
+        decisions: false,
+        chip_conservation: true,
+        ..default()
+    });
+    let errors = apply(&mut app, three_hole_cards());
+    assert!(!errors.iter().any(is_wrong_hole_cards), "{errors:?}");
+    assert!(app.world.get_resource::<DecisionMode>().is_none());
+    assert!(app.world.get_resource::<ChipConservation>().is_some());
+}
@@ -0,0 +1,34 @@
+use ecs_example::rake::{HandRake, RakeCap, RakeRules};
+
+#[test]
+fn rake_and_shares_stay_within_the_pot() {
+    let rake_rules = RakeRules {
+        rate_bps: 500,
+        caps: vec![RakeCap {
+            min_players: 2,
+            cap: 300,
+        }],
+        jackpot_fee: 100,
+        ..Default::default()
+    };
+    let hand_rake = rake_rules.rake(2001, 3, true);
+    assert_eq!((hand_rake.rake, hand_rake.jackpot_fee), (100, 100));
+    assert_eq!(hand_rake.shares(2), vec![901, 900]);
+
+    // Over 100% the house takes the whole pot, the jackpot fee gets nothing
+    let hand_rake = RakeRules {
+        rate_bps: 20_000,
+        caps: vec![],
+        ..rake_rules
+    }
+    .rake(1000, 3, true);
+    assert_eq!((hand_rake.rake, hand_rake.jackpot_fee), (1000, 0));
+    assert_eq!(hand_rake.shares(2), vec![0, 0]);
+
+    let hand_rake = HandRake {
+        pot: 100,
+        rake: 80,
+        jackpot_fee: 50,
+    };
+    assert_eq!(hand_rake.shares(3), vec![0, 0, 0]);
+}
@@ -0,0 +1,326 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use bevy::prelude::default;
+use serde::{Deserialize, Serialize};
+
+use ecs_example::action::{Action, Card};
+use ecs_example::betting::{BetRange, BettingRoundClosed, UncalledBetReturned};
+use ecs_example::components::{BlindSeats, GameLimit};
+use ecs_example::error::TrackerError;
+use ecs_example::plugin::{DisplayMode, PokerTrackerPlugin};
+use ecs_example::rake::HandRake;
+use ecs_example::session::{Outcome, TableSession};
+use ecs_example::view::{PlayerStatus, Position, Street, TableView, TournamentView};
+
+/// A hand written down as action batches, each followed by what the table
+/// must look like after the batch went through the full schedule.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Scenario {
+    pub name: String,
+    pub steps: Vec<ScenarioStep>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScenarioStep {
+    pub actions: Vec<Action>,
+    #[serde(default)]
+    pub expect: Expectations,
+}
+
+/// Checks after one batch. Missing fields are not checked, except the errors:
+/// a step without `error` or `errors` must be applied without errors.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Expectations {
+    /// The only error of the step.
+    pub error: Option<TrackerError>,
+    /// Every error of the step in order, when there are several.
+    pub errors: Vec<TrackerError>,
+    pub stacks: BTreeMap<String, u64>,
+    pub round_bets: BTreeMap<String, Vec<u64>>,
+    pub statuses: BTreeMap<String, PlayerStatus>,
+    pub positions: BTreeMap<String, Position>,
+    /// Players with `NeedDecision` set, `[]` when nobody has to act.
+    pub need_decision: Option<Vec<String>>,
+    /// Bet range of the player who needs a decision.
+    pub bet_range: Option<BetRange>,
+    pub board: Option<Vec<Card>>,
+    pub street: Option<Street>,
+    pub pot: Option<u64>,
+    pub round_max_bet: Option<u64>,
+    pub game_limit: Option<GameLimit>,
+    pub rake: Option<HandRake>,
+    pub blind_seats: Option<BlindSeats>,
+    /// Streets closed by the step, `[]` when none was.
+    pub rounds_closed: Option<Vec<BettingRoundClosed>>,
+    /// Bets returned by the step, `[]` when none was.
+    pub uncalled_bets: Option<Vec<UncalledBetReturned>>,
+    pub tournament: Option<TournamentView>,
+}
+
+#[derive(Debug)]
+pub enum ScenarioError {
+    Io(io::Error),
+    Format(serde_json::Error),
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioError::Io(err) => write!(f, "scenario io error: {err}"),
+            ScenarioError::Format(err) => write!(f, "scenario format error: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ScenarioError {}
+
+impl From<io::Error> for ScenarioError {
+    fn from(err: io::Error) -> Self {
+        ScenarioError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for ScenarioError {
+    fn from(err: serde_json::Error) -> Self {
+        ScenarioError::Format(err)
+    }
+}
+
+/// Every mismatch of one step, `step` counts from 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioFailure {
+    pub scenario: String,
+    pub step: usize,
+    pub mismatches: Vec<String>,
+}
+
+impl fmt::Display for ScenarioFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "scenario {:?} step {}:", self.scenario, self.step)?;
+        self.mismatches
+            .iter()
+            .try_for_each(|mismatch| write!(f, "\n  {mismatch}"))
+    }
+}
+
+impl std::error::Error for ScenarioFailure {}
+
+impl Scenario {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ScenarioError> {
+        let scenario_json = fs::read_to_string(path)?;
+
+        Ok(serde_json::from_str(&scenario_json)?)
+    }
+
+    /// Plays the steps on a fresh `TableSession` with the chip conservation check
+    /// and stops at the first step that does not match its expectations.
+    pub fn run(&self) -> Result<(), ScenarioFailure> {
+        let mut table_session = TableSession::with_plugin(PokerTrackerPlugin {
+            display: DisplayMode::Off,
+            chip_conservation: true,
+            ..default()
+        });
+
+        self.steps.iter().enumerate().try_for_each(|(index, step)| {
+            let (errors, outcome) = match table_session.apply(step.actions.clone()) {
+                Ok(outcome) => (outcome.warnings.clone(), outcome),
+                Err(tracker_errors) => (tracker_errors.0, Outcome::default()),
+            };
+            let mismatches = step
+                .expect
+                .mismatches(&errors, &outcome, &table_session.table_view());
+
+            if mismatches.is_empty() {
+                Ok(())
+            } else {
+                Err(ScenarioFailure {
+                    scenario: self.name.clone(),
+                    step: index + 1,
+                    mismatches,
+                })
+            }
+        })
+    }
+}
+
+impl Expectations {
+    /// Warnings are checked as errors. The outcome of a step with rejected actions is empty.
+    pub fn mismatches(
+        &self,
+        errors: &[TrackerError],
+        outcome: &Outcome,
+        table_view: &TableView,
+    ) -> Vec<String> {
+        let mut mismatches = vec![];
+        let mut check = |what: String, expected: String, actual: String| {
+            if expected != actual {
+                mismatches.push(format!("{what}: expected {expected}, got {actual}"));
+            }
+        };
+
+        let expected_errors = self.error.iter().chain(&self.errors).collect::<Vec<_>>();
+        check(
+            "errors".into(),
+            format!("{expected_errors:?}"),
+            format!("{:?}", errors.iter().collect::<Vec<_>>()),
+        );
+
+        self.stacks.iter().for_each(|(name, stack)| {
+            let player = table_view
+                .players
+                .iter()
+                .find(|player| &player.name == name);
+            check(
+                format!("stack of {name}"),
+                format!("{:?}", Some(stack)),
+                format!("{:?}", player.and_then(|player| player.stack.as_ref())),
+            );
+        });
+
+        self.round_bets.iter().for_each(|(name, round_bets)| {
+            let player = table_view
+                .players
+                .iter()
+                .find(|player| &player.name == name);
+            check(
+                format!("round bets of {name}"),
+                format!("{:?}", Some(round_bets)),
+                format!("{:?}", player.map(|player| &player.round_bets)),
+            );
+        });
+
+        self.statuses.iter().for_each(|(name, status)| {
+            let player = table_view
+                .players
+                .iter()
+                .find(|player| &player.name == name);
+            check(
+                format!("status of {name}"),
+                format!("{:?}", Some(status)),
+                format!("{:?}", player.map(|player| &player.status)),
+            );
+        });
+
+        self.positions.iter().for_each(|(name, position)| {
+            let player = table_view
+                .players
+                .iter()
+                .find(|player| &player.name == name);
+            check(
+                format!("position of {name}"),
+                format!("{:?}", Some(position)),
+                format!("{:?}", player.and_then(|player| player.position.as_ref())),
+            );
+        });
+
+        if let Some(need_decision) = &self.need_decision {
+            let actual_need_decision = table_view
+                .players
+                .iter()
+                .filter(|player| player.need_decision)
+                .map(|player| player.name.clone())
+                .collect::<Vec<_>>();
+            check(
+                "need decision".into(),
+                format!("{need_decision:?}"),
+                format!("{actual_need_decision:?}"),
+            );
+        }
+
+        if let Some(bet_range) = &self.bet_range {
+            let actual_bet_range = table_view
+                .players
+                .iter()
+                .find(|player| player.need_decision)
+                .and_then(|player| player.bet_range);
+            check(
+                "bet range".into(),
+                format!("{:?}", Some(bet_range)),
+                format!("{:?}", actual_bet_range.as_ref()),
+            );
+        }
+
+        if let Some(board) = &self.board {
+            check(
+                "board".into(),
+                format!("{board:?}"),
+                format!("{:?}", table_view.board),
+            );
+        }
+
+        if let Some(street) = &self.street {
+            check(
+                "street".into(),
+                format!("{street:?}"),
+                format!("{:?}", table_view.street),
+            );
+        }
+
+        if let Some(pot) = self.pot {
+            check("pot".into(), pot.to_string(), table_view.pot.to_string());
+        }
+
+        if let Some(round_max_bet) = self.round_max_bet {
+            check(
+                "round max bet".into(),
+                round_max_bet.to_string(),
+                table_view.round_max_bet.to_string(),
+            );
+        }
+
+        if let Some(game_limit) = &self.game_limit {
+            check(
+                "game limit".into(),
+                format!("{:?}", Some(game_limit)),
+                format!("{:?}", table_view.game_limit.as_ref()),
+            );
+        }
+
+        if let Some(rake) = &self.rake {
+            check(
+                "rake".into(),
+                format!("{:?}", Some(rake)),
+                format!("{:?}", table_view.rake.as_ref()),
+            );
+        }
+
+        if let Some(blind_seats) = &self.blind_seats {
+            check(
+                "blind seats".into(),
+                format!("{:?}", Some(blind_seats)),
+                format!("{:?}", table_view.blind_seats.as_ref()),
+            );
+        }
+
+        if let Some(rounds_closed) = &self.rounds_closed {
+            check(
+                "rounds closed".into(),
+                format!("{rounds_closed:?}"),
+                format!("{:?}", outcome.rounds_closed),
+            );
+        }
+
+        if let Some(uncalled_bets) = &self.uncalled_bets {
+            check(
+                "uncalled bets".into(),
+                format!("{uncalled_bets:?}"),
+                format!("{:?}", outcome.uncalled_bets),
+            );
+        }
+
+        if let Some(tournament) = &self.tournament {
+            check(
+                "tournament".into(),
+                format!("{:?}", Some(tournament)),
+                format!("{:?}", table_view.tournament.as_ref()),
+            );
+        }
+
+        mismatches
+    }
+}
@@ -0,0 +1,37 @@
+use std::fs;
+use std::path::Path;
+
+mod scenario;
+
+use scenario::Scenario;
+
+#[test]
+fn scenario_corpus() {
+    let scenarios_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/scenarios");
+    let mut scenario_paths = fs::read_dir(&scenarios_dir)
+        .expect("Can't read scenarios dir")
+        .map(|entry| entry.expect("Can't read scenarios dir").path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "json")
+        })
+        .collect::<Vec<_>>();
+    scenario_paths.sort();
+    assert!(
+        !scenario_paths.is_empty(),
+        "No scenarios in {scenarios_dir:?}"
+    );
+
+    let failures = scenario_paths
+        .iter()
+        .filter_map(|path| {
+            let scenario = match Scenario::from_file(path) {
+                Ok(scenario) => scenario,
+                Err(err) => return Some(format!("{path:?}: {err}")),
+            };
+            scenario.run().err().map(|failure| failure.to_string())
+        })
+        .collect::<Vec<_>>();
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
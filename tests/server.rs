@@ -0,0 +1,61 @@
+use std::net::{SocketAddr, TcpListener};
+use std::thread;
+
+use ecs_example::server::{self, ServerReply, TrackerClient};
+use ecs_example::*;
+
+mod common;
+
+use common::{bet, seat, stack};
+
+fn start_server() -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Can't bind test server");
+    let addr = listener.local_addr().expect("Test server has no address");
+    thread::spawn(move || server::serve_listeners(listener, None));
+
+    addr
+}
+
+#[test]
+fn bad_batch_is_rejected_and_server_keeps_answering() {
+    let addr = start_server();
+    let mut tracker_client = TrackerClient::connect(addr).expect("Can't connect to test server");
+
+    let reply = tracker_client
+        .send("probe", vec![seat("Ann", 1), seat("Bob", 2)])
+        .expect("No reply to seats");
+    assert!(matches!(reply, ServerReply::Ok { .. }), "{reply:?}");
+    tracker_client
+        .send("probe", vec![stack("Ann", 1000), stack("Bob", 1000)])
+        .expect("No reply to stacks");
+
+    // Bets before `GameHandIdSet`, every error of the batch comes back
+    let reply = tracker_client
+        .send("probe", vec![bet(1, 50), bet(2, 100)])
+        .expect("No reply to bets before the hand");
+    assert_eq!(
+        reply,
+        ServerReply::Rejected {
+            table: "probe".into(),
+            errors: vec![TrackerError::NoHand, TrackerError::NoHand],
+        }
+    );
+
+    let reply = tracker_client
+        .send(
+            "probe",
+            vec![
+                Action::GameHandIdSet("1".into()),
+                Action::GameTypeSet(GameType::NL),
+            ],
+        )
+        .expect("No reply after a rejected batch");
+    assert!(matches!(reply, ServerReply::Ok { .. }), "{reply:?}");
+
+    // Other connections and tables are still served
+    let mut other_client = TrackerClient::connect(addr).expect("Can't connect to test server");
+    let reply = other_client
+        .send("other", vec![seat("Carol", 3)])
+        .expect("No reply on another table");
+    assert!(matches!(reply, ServerReply::Ok { .. }), "{reply:?}");
+}
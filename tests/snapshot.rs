@@ -0,0 +1,93 @@
+use std::fs;
+
+use ecs_example::hand_history_parser::HandHistoryParser;
+use ecs_example::*;
+
+mod common;
+
+use common::{hand_history, stack};
+
+fn apply_all(session: &mut TableSession, batches: &[Vec<Action>]) {
+    for batch in batches {
+        if let Err(tracker_errors) = session.apply(batch.clone()) {
+            panic!("{batch:?} was rejected: {tracker_errors}");
+        }
+    }
+}
+
+#[test]
+fn restored_session_continues_the_hand() {
+    let batches = HandHistoryParser::default()
+        .parse_hand(&hand_history("simple_cash.txt"))
+        .expect("Can't parse hand");
+    // Right after the flop, Hero is asked to act
+    let flop_index = batches
+        .iter()
+        .position(|batch| matches!(batch[..], [Action::CommunityCardsDealt(_)]))
+        .expect("No flop in hand");
+    let (before_flop, after_flop) = batches.split_at(flop_index + 1);
+
+    let mut session = TableSession::new();
+    apply_all(&mut session, before_flop);
+    let snapshot_path =
+        std::env::temp_dir().join(format!("ecs-example-snapshot-{}.json", std::process::id()));
+    session
+        .save_snapshot(&snapshot_path)
+        .expect("Can't save snapshot");
+    let restored = TableSession::from_snapshot(&snapshot_path);
+    let _ = fs::remove_file(&snapshot_path);
+    let mut restored = restored.expect("Can't restore snapshot");
+
+    assert_eq!(restored.table_view(), session.table_view());
+    assert!(restored.pending_decision().is_some());
+    assert_eq!(restored.pending_decision(), session.pending_decision());
+    assert_eq!(restored.hand_history(), session.hand_history());
+    assert!(restored.hand_history().is_some());
+
+    apply_all(&mut session, after_flop);
+    apply_all(&mut restored, after_flop);
+    assert_eq!(restored.table_view(), session.table_view());
+    assert_eq!(restored.hand_history(), session.hand_history());
+}
+
+#[test]
+fn restored_session_keeps_its_plugin() {
+    let batches = HandHistoryParser::default()
+        .parse_hand(&hand_history("simple_cash.txt"))
+        .expect("Can't parse hand");
+    let plugin = PokerTrackerPlugin {
+        display: DisplayMode::Off,
+        chip_conservation: true,
+        ..Default::default()
+    };
+    let mut session = TableSession::with_plugin(plugin.clone());
+    apply_all(&mut session, &batches[..batches.len() / 2]);
+    let snapshot_path = std::env::temp_dir().join(format!(
+        "ecs-example-snapshot-plugin-{}.json",
+        std::process::id()
+    ));
+    session
+        .save_snapshot(&snapshot_path)
+        .expect("Can't save snapshot");
+    let checked = TableSession::from_snapshot_with_plugin(plugin, &snapshot_path);
+    let unchecked = TableSession::from_snapshot(&snapshot_path);
+    let _ = fs::remove_file(&snapshot_path);
+
+    // Chips out of nowhere in the middle of the hand
+    let drift = || vec![stack("Ann", 5000)];
+    let warnings = checked
+        .expect("Can't restore snapshot")
+        .apply(drift())
+        .expect("Stack update was rejected")
+        .warnings;
+    assert!(
+        matches!(warnings[..], [TrackerError::ChipsNotConserved { .. }]),
+        "{warnings:?}"
+    );
+    let warnings = unchecked
+        .expect("Can't restore snapshot")
+        .apply(drift())
+        .expect("Stack update was rejected")
+        .warnings;
+    assert_eq!(warnings, vec![]);
+}
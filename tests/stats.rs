@@ -0,0 +1,120 @@
+use std::fs;
+
+use ecs_example::hand_history_parser::HandHistoryParser;
+use ecs_example::stats::{PlayerStats, TableStats};
+use ecs_example::*;
+
+mod common;
+
+use common::{bet, hand_history, pot_awarded, seat, stack};
+
+#[test]
+fn stats_count_voluntary_bets_and_raises() {
+    let mut table_stats = TableStats::default();
+    HandHistoryParser::default()
+        .parse_hand(&hand_history("simple_cash.txt"))
+        .expect("Can't parse simple_cash.txt")
+        .iter()
+        .flatten()
+        .for_each(|action| table_stats.add_action(action));
+
+    // Hero completes the small blind with a 3-bet, bets the flop and the river
+    assert_eq!(
+        table_stats.player_stats("Hero"),
+        Some(PlayerStats {
+            name: "Hero".into(),
+            hands: 1,
+            vpip_hands: 1,
+            preflop_raise_hands: 1,
+            total_bet: 470,
+            total_bet_big_blinds: 47.0,
+        })
+    );
+    let bob_stats = table_stats.player_stats("Bob").expect("No stats of Bob");
+    assert_eq!(
+        (
+            bob_stats.vpip_hands,
+            bob_stats.preflop_raise_hands,
+            bob_stats.total_bet
+        ),
+        (1, 1, 220)
+    );
+    // Posting the big blind is not a voluntary bet
+    let dan_stats = table_stats.player_stats("Dan").expect("No stats of Dan");
+    assert_eq!(
+        (dan_stats.hands, dan_stats.vpip_hands, dan_stats.total_bet),
+        (1, 0, 10)
+    );
+    let ann_stats = table_stats.player_stats("Ann").expect("No stats of Ann");
+    assert_eq!(
+        (ann_stats.hands, ann_stats.vpip_hands, ann_stats.total_bet),
+        (1, 0, 0)
+    );
+    assert_eq!(table_stats.player_stats("Eve"), None);
+}
+
+#[test]
+fn stats_count_hands_trimmed_from_the_journal() {
+    let game = |hand_id: &str| {
+        vec![
+            Action::GameHandIdSet(hand_id.into()),
+            Action::GameTypeSet(GameType::NL),
+            Action::GameLimitSet(GameLimit::new(5, 10, Currency::Chips)),
+        ]
+    };
+    // Everybody folds to the big blind, the button moves on each hand
+    let batches = vec![
+        vec![seat("Ann", 1), seat("Bob", 2), seat("Carol", 3)],
+        game("1"),
+        vec![Action::GameDealerSeatNumSet(1)],
+        vec![stack("Ann", 1000), stack("Bob", 1000), stack("Carol", 1000)],
+        vec![bet(2, 5)],
+        vec![bet(3, 10)],
+        vec![bet(1, 0)],
+        vec![bet(2, 0)],
+        vec![pot_awarded("Carol", 10)],
+        game("2"),
+        vec![bet(3, 5)],
+        vec![bet(1, 10)],
+        vec![bet(2, 0)],
+        vec![bet(3, 0)],
+        vec![pot_awarded("Ann", 10)],
+        game("3"),
+        vec![bet(1, 5)],
+        vec![bet(2, 10)],
+        vec![bet(3, 0)],
+        vec![bet(1, 0)],
+        vec![pot_awarded("Bob", 10)],
+    ];
+    let mut session = TableSession::new();
+    for batch in &batches {
+        if let Err(tracker_errors) = session.apply(batch.clone()) {
+            panic!("{batch:?} was rejected: {tracker_errors}");
+        }
+    }
+
+    assert_eq!(
+        session.stats().player_stats("Ann"),
+        Some(PlayerStats {
+            name: "Ann".into(),
+            hands: 3,
+            vpip_hands: 0,
+            preflop_raise_hands: 0,
+            total_bet: 15,
+            total_bet_big_blinds: 1.5,
+        })
+    );
+    let mut journal_stats = PlayerStats::new("Ann");
+    journal_stats.add_journal(session.journal().actions());
+    assert_eq!(journal_stats.hands, 2);
+
+    let snapshot_path =
+        std::env::temp_dir().join(format!("ecs-example-stats-{}.json", std::process::id()));
+    session
+        .save_snapshot(&snapshot_path)
+        .expect("Can't save snapshot");
+    let restored = TableSession::from_snapshot(&snapshot_path);
+    let _ = fs::remove_file(&snapshot_path);
+    let restored = restored.expect("Can't restore snapshot");
+    assert_eq!(restored.stats(), session.stats());
+}
@@ -0,0 +1,76 @@
+use ecs_example::tui::{render_table, render_tables};
+use ecs_example::*;
+
+fn table_view() -> TableView {
+    let player = |name: &str, seat_num: u8, stack: u64| PlayerView {
+        name: name.into(),
+        seat_num,
+        status: PlayerStatus::Active,
+        stack: Some(stack),
+        ..Default::default()
+    };
+    TableView {
+        hand_id: Some("174088855475".into()),
+        game_type: Some(GameType::NL),
+        max_seats: Some(4),
+        street: Street::Flop,
+        board: vec![Card::H2, Card::S7, Card::D8],
+        pot: 400,
+        players: vec![
+            PlayerView {
+                dealer: true,
+                round_bets: vec![100],
+                ..player("Ann", 1, 900)
+            },
+            PlayerView {
+                cards: vec![Card::SK, Card::H2],
+                need_decision: true,
+                npc: true,
+                ..player("Hero", 2, 1200)
+            },
+            player("Bob", 4, 700),
+        ],
+        ..Default::default()
+    }
+}
+
+fn has_line(screen: &str, expected: &str) -> bool {
+    screen.lines().any(|line| line.contains(expected))
+}
+
+#[test]
+fn table_is_drawn_around_the_board() {
+    let screen = render_table(&table_view());
+
+    assert_eq!(screen.lines().count(), 23, "{screen}");
+    [
+        " [1] Ann (D)",
+        "900 Active",
+        "bet 100",
+        ">[2] Hero",
+        "1200 Active",
+        "Ks 2h",
+        "[3] empty",
+        " [4] Bob",
+        "#174088855475",
+        "Flop  2h 7s 8d",
+        "pot 400",
+        "Hero to act",
+    ]
+    .iter()
+    .for_each(|expected| assert!(has_line(&screen, expected), "No {expected:?} in\n{screen}"));
+    assert!(!has_line(&screen, "[5]"), "{screen}");
+}
+
+#[test]
+fn tables_are_drawn_under_their_titles() {
+    let table_view = table_view();
+    let screen = render_tables([("Aase III", &table_view), ("Tournament 2", &table_view)]);
+
+    let titles = screen
+        .lines()
+        .filter(|line| line.starts_with("=="))
+        .collect::<Vec<_>>();
+    assert_eq!(titles, vec!["== Aase III ==", "== Tournament 2 =="]);
+    assert_eq!(screen.lines().count(), 2 * 24);
+}
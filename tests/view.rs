@@ -0,0 +1,89 @@
+use ecs_example::hand_history_parser::HandHistoryParser;
+use ecs_example::*;
+
+mod common;
+
+use common::hand_history;
+
+/// Session with the batches of the hand applied up to the flop.
+fn session_on_the_flop(file_name: &str) -> TableSession {
+    let batches = HandHistoryParser::default()
+        .parse_hand(&hand_history(file_name))
+        .unwrap_or_else(|err| panic!("Can't parse {file_name}: {err}"));
+    let flop_index = batches
+        .iter()
+        .position(|batch| {
+            batch
+                .iter()
+                .any(|action| matches!(action, Action::CommunityCardsDealt(_)))
+        })
+        .expect("No flop in the hand");
+
+    let mut session = TableSession::new();
+    for batch in &batches[..=flop_index] {
+        if let Err(tracker_errors) = session.apply(batch.clone()) {
+            panic!("{batch:?} was rejected: {tracker_errors}");
+        }
+    }
+
+    session
+}
+
+#[test]
+fn table_view_is_one_snapshot_of_the_hand() {
+    let table_view = session_on_the_flop("simple_cash.txt").table_view();
+
+    assert_eq!(table_view.hand_id.as_deref(), Some("243571110001"));
+    assert_eq!(table_view.dealer_seat_num, Some(2));
+    assert_eq!(table_view.street, Street::Flop);
+    assert_eq!(table_view.board, vec![Card::H8, Card::C4, Card::S2]);
+    // Blinds, Hero's raise to 100 and Bob's call
+    assert_eq!(table_view.pot, 210);
+
+    let players = table_view
+        .players
+        .iter()
+        .map(|player| {
+            (
+                player.name.as_str(),
+                player.seat_num,
+                player.position,
+                player.status,
+                player.dealer,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        players,
+        vec![
+            (
+                "Ann",
+                1,
+                Some(Position::Cutoff),
+                PlayerStatus::Folded,
+                false
+            ),
+            ("Bob", 2, Some(Position::Button), PlayerStatus::Active, true),
+            (
+                "Hero",
+                3,
+                Some(Position::SmallBlind),
+                PlayerStatus::Active,
+                false
+            ),
+            (
+                "Dan",
+                5,
+                Some(Position::BigBlind),
+                PlayerStatus::Folded,
+                false
+            ),
+        ]
+    );
+
+    let hero = &table_view.players[2];
+    assert_eq!(hero.cards, vec![Card::SQ, Card::DQ]);
+    assert_eq!(hero.stack, Some(900));
+    assert_eq!(hero.committed(), 100);
+    assert!(table_view.players[1].cards.is_empty());
+}
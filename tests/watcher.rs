@@ -0,0 +1,99 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+use ecs_example::watcher::HandHistoryWatcher;
+use ecs_example::*;
+
+mod common;
+
+use common::hand_history;
+
+fn append(path: &Path, text: &str) {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(text.as_bytes()))
+        .unwrap_or_else(|err| panic!("Can't append to {path:?}: {err}"));
+}
+
+fn player_names(table_view: &TableView) -> Vec<&str> {
+    table_view
+        .players
+        .iter()
+        .map(|player| player.name.as_str())
+        .collect()
+}
+
+#[test]
+fn every_file_is_tracked_as_its_own_table() {
+    let dir = std::env::temp_dir().join(format!("ecs-example-watcher-{}", std::process::id()));
+    fs::create_dir_all(&dir).expect("Can't create watched dir");
+    let cash_path = dir.join("Aase III.txt");
+    let tournament_path = dir.join("Tournament 2964581230.txt");
+
+    let simple_cash = hand_history("simple_cash.txt");
+    let (cash_head, cash_tail) = simple_cash.split_at(simple_cash.find("*** FLOP ***").unwrap());
+    append(&cash_path, cash_head);
+    append(&tournament_path, &hand_history("ante_tournament.txt"));
+
+    let mut watcher = HandHistoryWatcher::new(
+        dir.clone(),
+        PokerTrackerPlugin {
+            display: DisplayMode::Off,
+            ..Default::default()
+        },
+    );
+    let reports = watcher.poll().expect("Can't poll watched dir");
+    assert_eq!(reports, vec![]);
+    let cash_view = watcher.session(&cash_path).unwrap().table_view();
+    assert_eq!(cash_view.hand_id, None, "Unfinished hand was applied");
+
+    append(&cash_path, cash_tail);
+    append(&cash_path, &hand_history("side_pot.txt"));
+    let reports = watcher.poll().expect("Can't poll watched dir");
+    assert_eq!(reports, vec![]);
+    let cash_view = watcher.session(&cash_path).unwrap().table_view();
+    let tournament_view = watcher.session(&tournament_path).unwrap().table_view();
+
+    // A broken hand is reported and skipped, a removed file drops its table
+    append(
+        &tournament_path,
+        "\n\nPokerStars Hand #208011612036: Razz\n*** SUMMARY ***\n\n",
+    );
+    fs::remove_file(&cash_path).expect("Can't remove watched file");
+    let reports = watcher.poll().expect("Can't poll watched dir");
+    let _ = fs::remove_dir_all(&dir);
+    assert!(
+        matches!(
+            reports.as_slice(),
+            [(path, TrackerError::HandSkipped(_))] if *path == tournament_path
+        ),
+        "{reports:?}"
+    );
+    assert!(watcher.session(&cash_path).is_none());
+    assert_eq!(
+        watcher
+            .session(&tournament_path)
+            .map(|session| session.table_view().hand_id),
+        Some(Some("208011612035".into()))
+    );
+
+    assert_eq!(cash_view.hand_id.as_deref(), Some("243571110002"));
+    assert_eq!(cash_view.table_name.as_deref(), Some("Aase III"));
+    assert_eq!(player_names(&cash_view), vec!["Ann", "Bob", "Hero"]);
+    let cash_stacks = cash_view
+        .players
+        .iter()
+        .map(|player| player.stack)
+        .collect::<Vec<_>>();
+    assert_eq!(cash_stacks, vec![Some(0), Some(500), Some(1670)]);
+
+    assert_eq!(tournament_view.hand_id.as_deref(), Some("208011612035"));
+    assert_eq!(tournament_view.table_name.as_deref(), Some("2964581230 3"));
+    assert_eq!(
+        player_names(&tournament_view),
+        vec!["Ann", "Bob", "Carol", "Hero"]
+    );
+}